@@ -33,10 +33,12 @@ export interface Message {
 
 export interface InputProto {
   inner: {
-    author_id: string;
-    chat_id: string;
-    body: string;
-    created_at: Date;
+    type: 'message';
+    inner: {
+      author_id: string;
+      chat_id: string;
+      body: string;
+    };
   };
 }
 
@@ -101,10 +103,12 @@ export default class XeedClient {
   public sendUtf8(chatId: string, text: string): void {
     const message: InputProto = {
       inner: {
-        author_id: this.user?.id as string,
-        chat_id: chatId,
-        body: text,
-        created_at: new Date(),
+        type: 'message',
+        inner: {
+          author_id: this.user?.id as string,
+          chat_id: chatId,
+          body: text,
+        },
       },
     };
 
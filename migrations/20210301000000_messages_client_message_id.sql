@@ -0,0 +1,3 @@
+-- Add migration script here
+ALTER TABLE messages ADD COLUMN IF NOT EXISTS client_message_id UUID;
+ALTER TABLE messages ADD CONSTRAINT messages_author_id_client_message_id_key UNIQUE (author_id, client_message_id);
@@ -0,0 +1,29 @@
+-- Add migration script here
+ALTER TABLE chats ADD COLUMN IF NOT EXISTS last_sequence BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE messages ADD COLUMN IF NOT EXISTS sequence BIGINT;
+
+UPDATE
+  messages
+SET
+  sequence = numbered.sequence
+FROM (
+  SELECT
+    id,
+    ROW_NUMBER() OVER (PARTITION BY chat_id ORDER BY created_at ASC, id ASC) AS sequence
+  FROM
+    messages) AS numbered
+WHERE
+  messages.id = numbered.id;
+
+UPDATE
+  chats
+SET
+  last_sequence = COALESCE((
+    SELECT
+      MAX(messages.sequence)
+    FROM messages
+    WHERE
+      messages.chat_id = chats.id), 0);
+
+ALTER TABLE messages ALTER COLUMN sequence SET NOT NULL;
+ALTER TABLE messages ADD CONSTRAINT messages_chat_id_sequence_key UNIQUE (chat_id, sequence);
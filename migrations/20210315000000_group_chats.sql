@@ -0,0 +1,11 @@
+-- Add migration script here
+ALTER TABLE chats ADD COLUMN IF NOT EXISTS title VARCHAR(64);
+ALTER TABLE chats ADD COLUMN IF NOT EXISTS description VARCHAR(256);
+ALTER TABLE chats ADD COLUMN IF NOT EXISTS avatar_id UUID;
+ALTER TABLE chats ADD CONSTRAINT chats_avatar_id_fkey FOREIGN KEY (avatar_id) REFERENCES avatars(id);
+-- Keep the first membership of each participant before enforcing uniqueness
+DELETE FROM chats_users a USING chats_users b
+WHERE a.chat_id = b.chat_id
+  AND a.user_id = b.user_id
+  AND (a.created_at > b.created_at OR (a.created_at = b.created_at AND a.id > b.id));
+ALTER TABLE chats_users ADD CONSTRAINT chats_users_chat_id_user_id_key UNIQUE (chat_id, user_id);
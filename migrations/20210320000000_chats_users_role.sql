@@ -0,0 +1,8 @@
+-- Add migration script here
+ALTER TABLE chats_users ADD COLUMN IF NOT EXISTS role VARCHAR(16) NOT NULL DEFAULT 'member';
+ALTER TABLE chats_users ADD CONSTRAINT chats_users_role_check CHECK (role IN ('owner', 'admin', 'member'));
+
+-- the first participant of every existing chat becomes its owner
+UPDATE chats_users SET role = 'owner' WHERE id IN (
+  SELECT DISTINCT ON (chat_id) id FROM chats_users ORDER BY chat_id, created_at ASC
+);
@@ -0,0 +1,48 @@
+-- Add migration script here
+ALTER TABLE chats ADD COLUMN IF NOT EXISTS kind VARCHAR(16) NOT NULL DEFAULT 'group';
+ALTER TABLE chats ADD CONSTRAINT chats_kind_check CHECK (kind IN ('direct', 'group'));
+
+-- a single direct chat is allowed for each pair of users, the pair is
+-- stored sorted so (a, b) and (b, a) are the same pair
+CREATE TABLE IF NOT EXISTS direct_chats (
+  chat_id UUID PRIMARY KEY,
+  first_user_id UUID NOT NULL,
+  second_user_id UUID NOT NULL,
+  created_at TIMESTAMP WITH TIME ZONE  NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  FOREIGN KEY(chat_id) REFERENCES chats(id),
+  FOREIGN KEY(first_user_id) REFERENCES users(id),
+  FOREIGN KEY(second_user_id) REFERENCES users(id),
+  UNIQUE (first_user_id, second_user_id),
+  CHECK (first_user_id < second_user_id)
+);
+
+-- chats between two users without a title created before direct chats
+-- existed become the direct chat of the pair, the oldest one if the pair
+-- has many of them
+INSERT INTO direct_chats (chat_id, first_user_id, second_user_id, created_at)
+SELECT DISTINCT ON (pairs.users_ids[1], pairs.users_ids[2])
+  pairs.chat_id,
+  pairs.users_ids[1],
+  pairs.users_ids[2],
+  pairs.created_at
+FROM (
+  SELECT
+    chats.id AS chat_id,
+    chats.created_at,
+    array_agg(chats_users.user_id ORDER BY chats_users.user_id) AS users_ids
+  FROM
+    chats
+    INNER JOIN chats_users ON chats_users.chat_id = chats.id
+  WHERE
+    chats.title IS NULL
+  GROUP BY
+    chats.id
+  HAVING
+    COUNT(*) = 2
+) AS pairs
+ORDER BY
+  pairs.users_ids[1],
+  pairs.users_ids[2],
+  pairs.created_at ASC;
+UPDATE chats SET kind = 'direct' WHERE id IN (SELECT chat_id FROM direct_chats);
+UPDATE chats_users SET role = 'member' WHERE chat_id IN (SELECT chat_id FROM direct_chats);
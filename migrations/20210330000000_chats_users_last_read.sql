@@ -0,0 +1,3 @@
+-- Add migration script here
+ALTER TABLE chats_users ADD COLUMN IF NOT EXISTS last_read_sequence BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE chats_users ADD COLUMN IF NOT EXISTS last_read_at TIMESTAMP WITH TIME ZONE;
@@ -0,0 +1,10 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS message_reactions (
+  message_id UUID NOT NULL,
+  user_id UUID NOT NULL,
+  emoji VARCHAR(64) NOT NULL,
+  created_at TIMESTAMP WITH TIME ZONE  NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY(message_id, user_id, emoji),
+  FOREIGN KEY(message_id) REFERENCES messages(id) ON DELETE CASCADE,
+  FOREIGN KEY(user_id) REFERENCES users(id)
+);
@@ -0,0 +1,3 @@
+-- Add migration script here
+ALTER TABLE messages ADD COLUMN IF NOT EXISTS reply_to_id UUID REFERENCES messages(id);
+CREATE INDEX IF NOT EXISTS messages_reply_to_id_idx ON messages (reply_to_id);
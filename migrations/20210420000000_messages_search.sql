@@ -0,0 +1,2 @@
+-- Add migration script here
+CREATE INDEX IF NOT EXISTS messages_content_search_idx ON messages USING GIN (to_tsvector('simple', content));
@@ -0,0 +1,11 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS chat_pins (
+  chat_id UUID NOT NULL,
+  message_id UUID NOT NULL,
+  pinned_by UUID NOT NULL,
+  pinned_at TIMESTAMP WITH TIME ZONE  NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY(chat_id, message_id),
+  FOREIGN KEY(chat_id) REFERENCES chats(id),
+  FOREIGN KEY(message_id) REFERENCES messages(id) ON DELETE CASCADE,
+  FOREIGN KEY(pinned_by) REFERENCES users(id)
+);
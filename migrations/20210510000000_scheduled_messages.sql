@@ -0,0 +1,18 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS scheduled_messages (
+  id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+  content TEXT NOT NULL,
+  kind VARCHAR(32) NOT NULL,
+  author_id UUID NOT NULL,
+  chat_id UUID NOT NULL,
+  file_id UUID,
+  reply_to_id UUID,
+  send_at TIMESTAMP WITH TIME ZONE NOT NULL,
+  created_at TIMESTAMP WITH TIME ZONE  NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  FOREIGN KEY(author_id) REFERENCES users(id),
+  FOREIGN KEY(chat_id) REFERENCES chats(id),
+  FOREIGN KEY(file_id) REFERENCES files(id),
+  FOREIGN KEY(reply_to_id) REFERENCES messages(id) ON DELETE SET NULL
+);
+CREATE INDEX IF NOT EXISTS scheduled_messages_send_at_idx ON scheduled_messages (send_at);
+CREATE INDEX IF NOT EXISTS scheduled_messages_chat_id_author_id_idx ON scheduled_messages (chat_id, author_id);
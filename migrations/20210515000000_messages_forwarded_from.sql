@@ -0,0 +1,4 @@
+-- Add migration script here
+ALTER TABLE messages ADD COLUMN IF NOT EXISTS forwarded_from_id UUID REFERENCES messages(id) ON DELETE SET NULL;
+ALTER TABLE messages ADD COLUMN IF NOT EXISTS forwarded_author_id UUID REFERENCES users(id);
+ALTER TABLE messages ADD COLUMN IF NOT EXISTS forwarded_chat_id UUID REFERENCES chats(id);
@@ -0,0 +1,5 @@
+-- Add migration script here
+ALTER TABLE chats_users ADD COLUMN IF NOT EXISTS muted_until TIMESTAMP WITH TIME ZONE;
+ALTER TABLE chats_users ADD COLUMN IF NOT EXISTS archived BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE chats_users ADD COLUMN IF NOT EXISTS pinned BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE chats_users ADD COLUMN IF NOT EXISTS position INTEGER;
@@ -0,0 +1,14 @@
+SELECT
+  message_reactions.message_id,
+  message_reactions.emoji,
+  COUNT(*) AS "count!",
+  ARRAY_AGG(message_reactions.user_id ORDER BY message_reactions.created_at) AS "users_ids!"
+FROM
+  message_reactions
+WHERE
+  message_reactions.message_id = ANY ($1)
+GROUP BY
+  message_reactions.message_id,
+  message_reactions.emoji
+ORDER BY
+  MIN(message_reactions.created_at) ASC;
@@ -0,0 +1,15 @@
+SELECT
+  chats_users.chat_id,
+  chats_users.last_read_sequence,
+  COUNT(messages.id) AS "unread_count!"
+FROM
+  chats_users
+  LEFT JOIN messages ON messages.chat_id = chats_users.chat_id
+    AND messages.sequence > chats_users.last_read_sequence
+    AND messages.author_id <> chats_users.user_id
+    AND messages.deleted_at IS NULL
+WHERE
+  chats_users.user_id = $1
+GROUP BY
+  chats_users.chat_id,
+  chats_users.last_read_sequence
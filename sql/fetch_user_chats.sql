@@ -1,15 +1,25 @@
 SELECT
-  STRING_AGG(user_id::text, ',') AS users,
-  chat_id
+  chats.id AS chat_id,
+  chats.kind AS chat_kind,
+  chats.title AS chat_title,
+  chats.description AS chat_description,
+  files.url AS "chat_avatar_url?",
+  chats.message_ttl AS chat_message_ttl,
+  ARRAY_AGG(chats_users.user_id ORDER BY chats_users.created_at) AS "participants_ids!",
+  ARRAY_AGG(chats_users.role ORDER BY chats_users.created_at) AS "participants_roles!"
 FROM
-  chats_users
+  chats
+  INNER JOIN chats_users ON chats_users.chat_id = chats.id
+  LEFT JOIN avatars ON avatars.id = chats.avatar_id
+  LEFT JOIN files ON files.id = avatars.file_id
 WHERE
-  chat_id IN (
+  chats.id IN (
     SELECT
       chat_id FROM chats_users
     WHERE
       user_id = $1)
 GROUP BY
-  chat_id
+  chats.id,
+  files.url
 ORDER BY
-  chat_id ASC
+  chats.id ASC
@@ -0,0 +1,19 @@
+SELECT
+  chats.id AS chat_id,
+  chats.kind AS chat_kind,
+  chats.title AS chat_title,
+  chats.description AS chat_description,
+  files.url AS "chat_avatar_url?",
+  chats.message_ttl AS chat_message_ttl,
+  ARRAY_AGG(chats_users.user_id ORDER BY chats_users.created_at) AS "participants_ids!",
+  ARRAY_AGG(chats_users.role ORDER BY chats_users.created_at) AS "participants_roles!"
+FROM
+  chats
+  INNER JOIN chats_users ON chats_users.chat_id = chats.id
+  LEFT JOIN avatars ON avatars.id = chats.avatar_id
+  LEFT JOIN files ON files.id = avatars.file_id
+WHERE
+  chats.id = $1
+GROUP BY
+  chats.id,
+  files.url
@@ -0,0 +1,25 @@
+SELECT
+  messages.id AS message_id,
+  messages."content" AS message_content,
+  messages.sequence AS message_sequence,
+  messages.created_at AS message_created_at,
+  messages.edited_at AS message_edited_at,
+  messages.deleted_at AS message_deleted_at,
+  messages.kind AS message_kind,
+  messages.reply_to_id AS message_reply_to_id,
+  messages.expires_at AS message_expires_at,
+  files.id AS "file_id?",
+  files.filename AS "file_filename?",
+  files.mime AS "file_mime?",
+  files.size AS "file_size?",
+  files.url AS "file_url?",
+  users.id AS author_id,
+  users. "name" AS author_name,
+  messages.chat_id AS chat_id
+FROM
+  messages
+  INNER JOIN users ON users.id = messages.author_id
+  LEFT JOIN files ON files.id = messages.file_id
+WHERE
+  messages.id = $1
+  AND messages.chat_id = $2;
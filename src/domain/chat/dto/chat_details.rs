@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+use super::deserialize_nullable;
+
+/// Details of a group chat provided when the chat is created or updated,
+/// details which are not provided are left untouched.
+///
+/// `title` and `description` are cleared when `null` is provided
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ChatDetailsDTO {
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub title: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub description: Option<Option<String>>,
+}
@@ -0,0 +1,44 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Settings of a chat provided by one of its participants, settings which
+/// are not provided are left untouched.
+///
+/// `muted_until` and `position` are cleared when `null` is provided
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ChatSettingsDTO {
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub muted_until: Option<Option<DateTime<Utc>>>,
+    #[serde(default)]
+    pub archived: Option<bool>,
+    #[serde(default)]
+    pub pinned: Option<bool>,
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub position: Option<Option<i32>>,
+}
+
+/// Deserializes a provided field as `Some`, even if its value is `null`,
+/// to tell apart fields set to `null` from missing fields
+pub fn deserialize_nullable<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_tells_apart_null_from_missing_settings() {
+        let settings: ChatSettingsDTO =
+            serde_json::from_str(r#"{ "muted_until": null, "pinned": true }"#).unwrap();
+
+        assert_eq!(settings.muted_until, Some(None));
+        assert_eq!(settings.position, None);
+        assert_eq!(settings.pinned, Some(true));
+        assert_eq!(settings.archived, None);
+    }
+}
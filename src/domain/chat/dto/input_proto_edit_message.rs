@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Replaces the body of a message previously sent by the client's user
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct InputProtoEditMessageDTO {
+    pub chat_id: Uuid,
+    pub message_id: Uuid,
+    pub body: String,
+}
+
+/// Deletes a message previously sent by the client's user
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct InputProtoDeleteMessageDTO {
+    pub chat_id: Uuid,
+    pub message_id: Uuid,
+}
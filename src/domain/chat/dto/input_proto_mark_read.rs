@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Marks the messages of a chat as read up to the message with the
+/// provided `sequence` number
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct InputProtoMarkReadDTO {
+    pub chat_id: Uuid,
+    pub sequence: i64,
+}
@@ -1,11 +1,34 @@
-use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::domain::chat::entity::MessageKind;
+
+/// A message sent by a client through the chat WebSocket.
+///
+/// The author of the message is always the user authenticated on the
+/// WebSocket, `author_id` is kept for compatibility with clients which
+/// send it and must match the authenticated user if provided.
+///
+/// Clients may provide a `client_message_id` to be acknowledged once the
+/// message is stored, sending a message with the same `client_message_id`
+/// again is acknowledged without storing the message twice.
+///
+/// `image` and `file` messages must provide the `file_id` of a file
+/// uploaded by the author, the `body` is used as caption.
+///
+/// Replies provide the ID of a message of the same chat on `reply_to`.
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct InputProtoMessageDTO {
-    pub author_id: Uuid,
+    #[serde(default)]
+    pub author_id: Option<Uuid>,
     pub chat_id: Uuid,
     pub body: String,
-    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub client_message_id: Option<Uuid>,
+    #[serde(default)]
+    pub kind: MessageKind,
+    #[serde(default)]
+    pub file_id: Option<Uuid>,
+    #[serde(default)]
+    pub reply_to: Option<Uuid>,
 }
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Adds or removes a reaction of the client's user to a message
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct InputProtoReactionDTO {
+    pub chat_id: Uuid,
+    pub message_id: Uuid,
+    pub emoji: String,
+}
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Resume handshake sent by a client after reconnecting, providing the
+/// sequence number of the last message received for each chat
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct InputProtoResumeDTO {
+    pub chats: Vec<ResumeChatDTO>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ResumeChatDTO {
+    pub chat_id: Uuid,
+    pub last_sequence: i64,
+}
@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Notifies the participants of a chat the client's user started or
+/// stopped typing
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct InputProtoTypingDTO {
+    pub chat_id: Uuid,
+}
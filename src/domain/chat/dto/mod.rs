@@ -1,3 +1,17 @@
+mod chat_details;
+mod chat_settings;
+mod input_proto_edit_message;
+mod input_proto_mark_read;
 mod input_proto_message;
+mod input_proto_reaction;
+mod input_proto_resume;
+mod input_proto_typing;
 
+pub use chat_details::*;
+pub use chat_settings::*;
+pub use input_proto_edit_message::*;
+pub use input_proto_mark_read::*;
 pub use input_proto_message::*;
+pub use input_proto_reaction::*;
+pub use input_proto_resume::*;
+pub use input_proto_typing::*;
@@ -1,8 +1,207 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
 use uuid::Uuid;
 
+use crate::error::{Error, Result};
+
+/// Max amount of seconds messages of a chat can be kept for before being
+/// purged, one year
+pub const MAX_MESSAGE_TTL: i32 = 60 * 60 * 24 * 365;
+
+/// A conversation between two or more participants.
+///
+/// Group chats may have a `title`, a `description` and an avatar, and its
+/// participants may change over time. Direct chats are kept between the
+/// same two participants.
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct Chat {
     pub id: Uuid,
+    pub kind: ChatKind,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub avatar_url: Option<String>,
+    /// Seconds new messages are kept for before being purged, messages are
+    /// kept forever if not provided
+    pub message_ttl: Option<i32>,
     pub participants_ids: Vec<Uuid>,
+    pub roles: BTreeMap<Uuid, ChatRole>,
+}
+
+impl Chat {
+    /// Retrieves the role of the participant with the provided `user_id`
+    pub fn role_of(&self, user_id: &Uuid) -> Option<ChatRole> {
+        self.roles.get(user_id).copied()
+    }
+
+    pub fn owner_id(&self) -> Option<Uuid> {
+        self.roles
+            .iter()
+            .find(|(_, role)| **role == ChatRole::Owner)
+            .map(|(user_id, _)| *user_id)
+    }
+
+    pub fn is_direct(&self) -> bool {
+        self.kind == ChatKind::Direct
+    }
+
+    /// Makes sure the chat is a group chat, direct chats can't be modified
+    pub fn ensure_group(&self) -> Result<()> {
+        if self.is_direct() {
+            return Err(Error::DirectChatNotModifiable(self.id));
+        }
+
+        Ok(())
+    }
+
+    /// Makes sure the participant with the provided `user_id` has at least
+    /// the `required` role to perform the `action` described
+    pub fn authorize(&self, user_id: &Uuid, required: ChatRole, action: &str) -> Result<()> {
+        match self.role_of(user_id) {
+            Some(role) if role >= required => Ok(()),
+            _ => Err(Error::ChatActionNotAllowed(
+                *user_id,
+                self.id,
+                action.to_string(),
+            )),
+        }
+    }
+
+    /// Makes sure the participant with the provided `user_id` has a higher
+    /// role than the participant with the provided `participant_id` to
+    /// perform the `action` described
+    pub fn authorize_over(
+        &self,
+        user_id: &Uuid,
+        participant_id: &Uuid,
+        action: &str,
+    ) -> Result<()> {
+        match (self.role_of(user_id), self.role_of(participant_id)) {
+            (Some(role), Some(participant_role)) if role > participant_role => Ok(()),
+            _ => Err(Error::ChatActionNotAllowed(
+                *user_id,
+                self.id,
+                action.to_string(),
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatKind {
+    Direct,
+    #[default]
+    Group,
+}
+
+impl FromStr for ChatKind {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "direct" => Ok(ChatKind::Direct),
+            "group" => Ok(ChatKind::Group),
+            _ => Err(Error::InvalidChatKind(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for ChatKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChatKind::Direct => write!(f, "direct"),
+            ChatKind::Group => write!(f, "group"),
+        }
+    }
+}
+
+/// Makes sure the provided `message_ttl` is within the allowed range, a
+/// missing TTL disables message expiration
+pub fn validate_message_ttl(message_ttl: Option<i32>) -> Result<()> {
+    match message_ttl {
+        Some(ttl) if !(1..=MAX_MESSAGE_TTL).contains(&ttl) => Err(Error::InvalidMessageTtl(ttl)),
+        _ => Ok(()),
+    }
+}
+
+/// Role of a participant in a `Chat`, roles are sorted from the one with
+/// less permissions to the one with more permissions.
+///
+/// Every chat has a single `ChatRole::Owner`, `ChatRole::Admin`s are able
+/// to moderate the chat along with the owner.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatRole {
+    #[default]
+    Member,
+    Admin,
+    Owner,
+}
+
+impl FromStr for ChatRole {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "member" => Ok(ChatRole::Member),
+            "admin" => Ok(ChatRole::Admin),
+            "owner" => Ok(ChatRole::Owner),
+            _ => Err(Error::InvalidChatRole(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for ChatRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChatRole::Member => write!(f, "member"),
+            ChatRole::Admin => write!(f, "admin"),
+            ChatRole::Owner => write!(f, "owner"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_authorizes_actions_over_lower_roles_only() {
+        let (owner_id, admin_id, member_id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let chat = Chat {
+            id: Uuid::new_v4(),
+            kind: ChatKind::Group,
+            title: None,
+            description: None,
+            avatar_url: None,
+            message_ttl: None,
+            participants_ids: vec![owner_id, admin_id, member_id],
+            roles: vec![
+                (owner_id, ChatRole::Owner),
+                (admin_id, ChatRole::Admin),
+                (member_id, ChatRole::Member),
+            ]
+            .into_iter()
+            .collect(),
+        };
+
+        assert_eq!(chat.owner_id(), Some(owner_id));
+        assert!(chat.authorize(&admin_id, ChatRole::Admin, "test").is_ok());
+        assert!(chat.authorize(&member_id, ChatRole::Admin, "test").is_err());
+        assert!(chat.authorize_over(&admin_id, &member_id, "test").is_ok());
+        assert!(chat.authorize_over(&admin_id, &owner_id, "test").is_err());
+        assert!(chat.authorize_over(&admin_id, &admin_id, "test").is_err());
+    }
+
+    #[test]
+    fn it_validates_message_ttls() {
+        assert!(validate_message_ttl(None).is_ok());
+        assert!(validate_message_ttl(Some(1)).is_ok());
+        assert!(validate_message_ttl(Some(MAX_MESSAGE_TTL)).is_ok());
+        assert!(validate_message_ttl(Some(0)).is_err());
+        assert!(validate_message_ttl(Some(-60)).is_err());
+        assert!(validate_message_ttl(Some(MAX_MESSAGE_TTL + 1)).is_err());
+    }
 }
@@ -1,10 +1,10 @@
 use futures::stream::SplitStream;
-use futures::{future, Stream, StreamExt, TryStream, TryStreamExt};
+use futures::{future, Stream, StreamExt};
 use std::str::FromStr;
 use uuid::Uuid;
 use warp::filters::ws::WebSocket;
 
-use crate::domain::chat::{Input, Output, Parcel, Proto};
+use crate::domain::chat::{Input, InputFrame, Output, Proto};
 use crate::domain::user::User;
 use crate::error::{Error, Result};
 
@@ -59,6 +59,13 @@ impl Client {
         }
     }
 
+    /// Reads `Proto<Input>` instances from the client's WebSocket stream.
+    ///
+    /// The stream ends as soon as the WebSocket fails or a non-text message
+    /// is received, text messages which are not a valid `Proto<Input>` are
+    /// yielded as `Error::JsonParsingError` without ending the stream.
+    ///
+    /// Legacy frames carrying a bare message are read as `Input::Message`.
     pub fn read_input(
         &self,
         stream: SplitStream<WebSocket>,
@@ -74,51 +81,25 @@ impl Client {
             .map(move |message| match message {
                 Err(err) => Err(Error::IO(err.to_string())),
                 Ok(message) => {
-                    let input: Proto<Input> =
-                        serde_json::from_str(message.to_str().unwrap()).unwrap();
+                    // safe to unwrap given that non-text messages are
+                    // filtered out before
+                    let frame: Proto<InputFrame> = serde_json::from_str(message.to_str().unwrap())?;
 
-                    Ok(input)
+                    Ok(Proto::new(frame.inner.into()))
                 }
             })
     }
 
-    pub fn write_output<S, E>(&self, stream: S) -> impl Stream<Item = Result<warp::ws::Message>>
+    /// Serializes the `Proto<Output>` instances delivered to one of the
+    /// `Client`'s connections into WebSocket messages
+    pub fn write_output<S>(&self, stream: S) -> impl Stream<Item = Result<warp::ws::Message>>
     where
-        S: TryStream<Ok = Proto<Output>, Error = E>
-            + Stream<Item = std::result::Result<Proto<Output>, E>>,
-        E: std::error::Error,
+        S: Stream<Item = Proto<Output>>,
     {
-        let user_id = self.user_id;
-        let frontend = self.frontend.clone();
+        stream.map(|output_proto| {
+            let data = serde_json::to_string(&output_proto.inner.parcel)?;
 
-        stream
-            .try_filter(
-                move |output_proto| match output_proto.inner.parcel.clone() {
-                    Parcel::LocalMessage(message) => {
-                        if message.author.id == user_id && matches!(frontend, FrontEnd::Browser) {
-                            return future::ready(false);
-                        }
-
-                        if message
-                            .chat
-                            .participants_ids
-                            .iter()
-                            .any(|pid| *pid == user_id)
-                        {
-                            return future::ready(true);
-                        }
-
-                        future::ready(false)
-                    }
-                    Parcel::Poll => future::ready(true),
-                    _ => future::ready(false),
-                },
-            )
-            .map_ok(|output_proto| {
-                let data = serde_json::to_string(&output_proto.inner.parcel).unwrap();
-
-                warp::ws::Message::text(data)
-            })
-            .map_err(|err| Error::IO(err.to_string()))
+            Ok(warp::ws::Message::text(data))
+        })
     }
 }
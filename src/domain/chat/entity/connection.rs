@@ -0,0 +1,232 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+use tokio::time::{timeout_at, Instant};
+use uuid::Uuid;
+
+use crate::domain::chat::{Client, Output, Parcel, Proto};
+use crate::error::{Error, Result};
+
+/// Max amount of `Proto<Output>` instances a single connection is able
+/// to hold before being considered a slow consumer.
+pub const CONNECTION_QUEUE_CAPACITY: usize = 64;
+
+/// A single WebSocket connection (device) of a `Client`.
+///
+/// Every connection owns a bounded queue of `Proto<Output>` instances
+/// which is drained by the connection's WebSocket sink. When the queue
+/// is full the connection is considered lagged and must be dropped by
+/// its owner, closing the WebSocket so the client is able to reconnect.
+///
+/// Clones of a `Connection` share the same queue, the WebSocket is not
+/// closed until every clone is dropped.
+#[derive(Clone, Debug)]
+pub struct Connection {
+    pub id: Uuid,
+    pub client: Client,
+    output_tx: Sender<Proto<Output>>,
+    held: Arc<Mutex<Option<Vec<Proto<Output>>>>>,
+}
+
+impl Connection {
+    /// Creates a new `Connection` for the provided `Client` and
+    /// retrieves the `Receiver` half of the connection's queue
+    pub fn new(client: Client) -> (Self, Receiver<Proto<Output>>) {
+        let (output_tx, output_rx) = channel(CONNECTION_QUEUE_CAPACITY);
+
+        (
+            Self {
+                id: Uuid::new_v4(),
+                client,
+                output_tx,
+                held: Arc::new(Mutex::new(None)),
+            },
+            output_rx,
+        )
+    }
+
+    pub fn user_id(&self) -> Uuid {
+        self.client.user_id
+    }
+
+    /// Enqueues a `Proto<Output>` without waiting for room in the
+    /// connection's queue.
+    ///
+    /// Fails with `Error::ConnectionLagged` if the queue is full and with
+    /// `Error::ConnectionClosed` if the receiver half is gone.
+    pub fn send(&self, proto: Proto<Output>) -> Result<()> {
+        let mut held = self.held.lock().unwrap();
+
+        if let Some(held) = held.as_mut() {
+            if held.len() >= CONNECTION_QUEUE_CAPACITY {
+                return Err(Error::ConnectionLagged(self.id));
+            }
+
+            held.push(proto);
+
+            return Ok(());
+        }
+
+        self.try_send(proto)
+    }
+
+    /// Holds back every `Proto<Output>` sent to the connection until
+    /// `release` is called
+    pub fn hold(&self) {
+        let mut held = self.held.lock().unwrap();
+
+        if held.is_none() {
+            *held = Some(Vec::new());
+        }
+    }
+
+    /// Enqueues the `replay` instances, waiting for room in the connection's
+    /// queue, followed by the instances held back since `hold` was called.
+    ///
+    /// The connection is considered lagged if the `replay` is not drained
+    /// within `timeout`, the replay may be larger than the queue so callers
+    /// are expected to release connections on their own task.
+    ///
+    /// Held `Parcel::LocalMessage` instances which are part of the `replay`
+    /// are sent only once.
+    pub async fn release(&self, replay: Vec<Proto<Output>>, timeout: Duration) -> Result<()> {
+        let deadline = Instant::now() + timeout;
+        let mut replayed_ids: HashSet<Uuid> = HashSet::new();
+
+        for proto in replay.into_iter() {
+            if let Parcel::LocalMessage(message) = &proto.inner.parcel {
+                replayed_ids.insert(message.id);
+            }
+
+            match timeout_at(deadline, self.output_tx.clone().send(proto)).await {
+                Ok(Ok(_)) => {}
+                Ok(Err(_)) => return Err(Error::ConnectionClosed(self.id)),
+                Err(_) => return Err(Error::ConnectionLagged(self.id)),
+            }
+        }
+
+        let mut held = self.held.lock().unwrap();
+
+        for proto in held.take().unwrap_or_default().into_iter() {
+            if let Parcel::LocalMessage(message) = &proto.inner.parcel {
+                if replayed_ids.contains(&message.id) {
+                    continue;
+                }
+            }
+
+            self.try_send(proto)?;
+        }
+
+        Ok(())
+    }
+
+    fn try_send(&self, proto: Proto<Output>) -> Result<()> {
+        match self.output_tx.clone().try_send(proto) {
+            Ok(_) => Ok(()),
+            Err(TrySendError::Full(_)) => Err(Error::ConnectionLagged(self.id)),
+            Err(TrySendError::Closed(_)) => Err(Error::ConnectionClosed(self.id)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use crate::domain::chat::{Chat, Message};
+    use crate::domain::user::User;
+
+    use super::*;
+
+    fn make_message_proto(sequence: i64) -> Proto<Output> {
+        Proto::new_output(Parcel::LocalMessage(Message {
+            id: Uuid::new_v4(),
+            body: String::from("Hello world!"),
+            kind: Default::default(),
+            attachment: None,
+            reactions: vec![],
+            mentions: vec![],
+            reply_to: None,
+            quote: None,
+            forwarded_from: None,
+            chat: Chat {
+                id: Uuid::nil(),
+                kind: Default::default(),
+                title: None,
+                description: None,
+                avatar_url: None,
+                message_ttl: None,
+                participants_ids: vec![],
+                roles: Default::default(),
+            },
+            author: User::new(Uuid::nil(), "foobar"),
+            sequence,
+            created_at: Utc::now(),
+            edited_at: None,
+            deleted_at: None,
+            expires_at: None,
+        }))
+    }
+
+    #[tokio::test]
+    async fn it_releases_held_output_after_replay() {
+        let (connection, mut output_rx) = Connection::new(Client::default());
+        let replayed = make_message_proto(1);
+        let live = make_message_proto(2);
+
+        connection.hold();
+        connection.send(replayed.clone()).unwrap();
+        connection.send(live).unwrap();
+        connection
+            .release(vec![replayed], Duration::from_secs(1))
+            .await
+            .unwrap();
+        drop(connection);
+
+        let mut sequences: Vec<i64> = Vec::new();
+
+        while let Some(proto) = output_rx.recv().await {
+            if let Parcel::LocalMessage(message) = proto.inner.parcel {
+                sequences.push(message.sequence);
+            }
+        }
+
+        assert_eq!(sequences, vec![1, 2]);
+    }
+
+    #[tokio::test]
+    async fn it_replays_more_output_than_the_queue_capacity() {
+        let (connection, mut output_rx) = Connection::new(Client::default());
+        let replay_len = CONNECTION_QUEUE_CAPACITY * 3;
+        let replay: Vec<Proto<Output>> = (1..=replay_len as i64).map(make_message_proto).collect();
+
+        connection.hold();
+
+        let release =
+            tokio::spawn(async move { connection.release(replay, Duration::from_secs(5)).await });
+        let mut received = 0;
+
+        while output_rx.recv().await.is_some() {
+            received += 1;
+        }
+
+        assert!(release.await.unwrap().is_ok());
+        assert_eq!(received, replay_len);
+    }
+
+    #[tokio::test]
+    async fn it_gives_up_replaying_to_stalled_connections() {
+        let (connection, _output_rx) = Connection::new(Client::default());
+        let replay: Vec<Proto<Output>> = (1..=CONNECTION_QUEUE_CAPACITY as i64 + 1)
+            .map(make_message_proto)
+            .collect();
+
+        connection.hold();
+
+        let result = connection.release(replay, Duration::from_millis(50)).await;
+
+        assert!(matches!(result, Err(Error::ConnectionLagged(_))));
+    }
+}
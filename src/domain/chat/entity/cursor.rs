@@ -0,0 +1,38 @@
+use serde::Serialize;
+
+use super::Message;
+
+/// Position in the history of a chat to fetch messages from, based on
+/// message sequence numbers
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MessagesCursor {
+    /// The most recent messages of the chat
+    Latest,
+    /// Messages older than the message with the provided sequence number
+    Before(i64),
+    /// Messages newer than the message with the provided sequence number
+    After(i64),
+}
+
+/// A page of messages of a chat sorted from the oldest to the newest.
+///
+/// `previous` and `next` are the cursors to fetch the older and newer
+/// pages respectively, `None` if there are no more messages to fetch.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct MessagesPage {
+    pub messages: Vec<Message>,
+    pub previous: Option<i64>,
+    pub next: Option<i64>,
+}
+
+/// A message along with a page of its replies sorted from the oldest to
+/// the newest.
+///
+/// `next` is the cursor to fetch the newer replies, `None` if there are no
+/// more replies to fetch.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Thread {
+    pub message: Message,
+    pub replies: Vec<Message>,
+    pub next: Option<i64>,
+}
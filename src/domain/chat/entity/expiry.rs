@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Messages of a chat purged once their chat's `message_ttl` elapsed,
+/// clients are expected to remove them from their local history
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct MessagesExpired {
+    pub chat_id: Uuid,
+    pub messages_ids: Vec<Uuid>,
+}
@@ -0,0 +1,97 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::user::{is_valid_username, USERNAME_CHARS};
+
+lazy_static! {
+    static ref MENTION_REGEX: Regex = Regex::new(&format!("@([{}]+)", USERNAME_CHARS)).unwrap();
+}
+
+/// Max amount of different users mentioned in a single message
+pub const MAX_MENTIONS: usize = 20;
+
+/// A mention of an user in the body of a `Message`.
+///
+/// `offset` and `length` are measured in characters and cover the whole
+/// `@username` token
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Mention {
+    pub user_id: Uuid,
+    pub offset: i32,
+    pub length: i32,
+}
+
+/// A `@username` token found in the body of a message
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MentionToken<'a> {
+    pub username: &'a str,
+    pub offset: i32,
+    pub length: i32,
+}
+
+/// Finds the `@username` tokens of the provided `body`.
+///
+/// Tokens must not be preceded nor followed by username characters, given
+/// that a dot at the end of a token is taken as punctuation, usernames
+/// ending with a dot can't be mentioned
+pub fn parse_mentions(body: &str) -> Vec<MentionToken<'_>> {
+    MENTION_REGEX
+        .captures_iter(body)
+        .filter_map(|captures| {
+            let start = captures.get(0)?.start();
+            let username = captures.get(1)?;
+            let end = username.start() + username.as_str().trim_end_matches('.').len();
+            let username = &body[username.start()..end];
+            let preceded = body[..start]
+                .chars()
+                .next_back()
+                .is_some_and(is_mention_char);
+            let followed = body[end..]
+                .chars()
+                .next()
+                .is_some_and(|c| c != '.' && is_mention_char(c));
+
+            if preceded || followed || !is_valid_username(username) {
+                return None;
+            }
+
+            Some(MentionToken {
+                username,
+                offset: body[..start].chars().count() as i32,
+                length: body[start..end].chars().count() as i32,
+            })
+        })
+        .collect()
+}
+
+fn is_mention_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '.' || c == '_' || c == '@'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_mentions() {
+        let tokens = parse_mentions("¡Hola @esteban.borai! cc @foo @foobarbaz. and me@example.com");
+
+        assert_eq!(
+            tokens,
+            vec![
+                MentionToken {
+                    username: "esteban.borai",
+                    offset: 6,
+                    length: 14,
+                },
+                MentionToken {
+                    username: "foobarbaz",
+                    offset: 30,
+                    length: 10,
+                },
+            ]
+        );
+    }
+}
@@ -1,17 +1,125 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 use uuid::Uuid;
 
-use domain::chat::Chat;
+use domain::chat::{Chat, Mention, ReactionCount};
 use domain::user::User;
 
 use crate::domain;
+use crate::error::Error;
+
+/// Max amount of characters of the body of a replied message kept in its
+/// `Quote`
+pub const QUOTE_BODY_LENGTH: usize = 120;
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct Message {
     pub id: Uuid,
     pub body: String,
+    pub kind: MessageKind,
+    /// File attached to `image` and `file` messages
+    pub attachment: Option<Attachment>,
+    pub reactions: Vec<ReactionCount>,
+    pub mentions: Vec<Mention>,
+    /// ID of the message this message replies to
+    pub reply_to: Option<Uuid>,
+    /// Preview of the message this message replies to
+    pub quote: Option<Quote>,
+    /// Provenance of forwarded messages
+    pub forwarded_from: Option<Forward>,
     pub chat: Chat,
     pub author: User,
+    /// Position of the message in its chat, greater for newer messages
+    pub sequence: i64,
     pub created_at: DateTime<Utc>,
+    pub edited_at: Option<DateTime<Utc>>,
+    /// Deleted messages are kept as tombstones with an empty `body`
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// Messages of chats with a `message_ttl` are purged once they expire
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageKind {
+    #[default]
+    Text,
+    Image,
+    File,
+}
+
+impl FromStr for MessageKind {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(MessageKind::Text),
+            "image" => Ok(MessageKind::Image),
+            "file" => Ok(MessageKind::File),
+            _ => Err(Error::InvalidMessageKind(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for MessageKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MessageKind::Text => write!(f, "text"),
+            MessageKind::Image => write!(f, "image"),
+            MessageKind::File => write!(f, "file"),
+        }
+    }
+}
+
+/// Compact preview of a replied `Message`, the `body` of the message is
+/// truncated to `QUOTE_BODY_LENGTH` characters
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Quote {
+    pub id: Uuid,
+    pub author: User,
+    pub body: String,
+    pub kind: MessageKind,
+    pub deleted: bool,
+}
+
+impl Quote {
+    pub fn new(id: Uuid, author: User, body: &str, kind: MessageKind, deleted: bool) -> Self {
+        Self {
+            id,
+            author,
+            body: body.chars().take(QUOTE_BODY_LENGTH).collect(),
+            kind,
+            deleted,
+        }
+    }
+}
+
+/// Original author and chat of a forwarded `Message`, messages forwarded
+/// again keep the provenance of the first forward.
+///
+/// The `message_id` is missing once the original message is purged
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Forward {
+    pub message_id: Option<Uuid>,
+    pub chat_id: Uuid,
+    pub author: User,
+}
+
+/// Metadata of an uploaded `File` attached to a `Message`, the file
+/// contents are available for download on `url`
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Attachment {
+    pub id: Uuid,
+    pub filename: String,
+    pub mime: String,
+    pub size: i32,
+    pub url: String,
+}
+
+impl Attachment {
+    pub fn is_image(&self) -> bool {
+        self.mime.starts_with("image/")
+    }
 }
@@ -1,9 +1,31 @@
 mod chat;
 mod client;
+mod connection;
+mod cursor;
+mod expiry;
+mod mention;
 mod message;
+mod pin;
+mod presence;
 mod proto;
+mod reaction;
+mod read_state;
+mod scheduled_message;
+mod search;
+mod settings;
 
 pub use chat::*;
 pub use client::*;
+pub use connection::*;
+pub use cursor::*;
+pub use expiry::*;
+pub use mention::*;
 pub use message::*;
+pub use pin::*;
+pub use presence::*;
 pub use proto::*;
+pub use reaction::*;
+pub use read_state::*;
+pub use scheduled_message::*;
+pub use search::*;
+pub use settings::*;
@@ -0,0 +1,22 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::Message;
+
+/// A `Message` pinned to its chat by one of the chat participants
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Pin {
+    pub chat_id: Uuid,
+    pub message: Message,
+    pub pinned_by: Uuid,
+    pub pinned_at: DateTime<Utc>,
+}
+
+/// A `Message` unpinned from its chat by one of the chat participants
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Unpin {
+    pub chat_id: Uuid,
+    pub message_id: Uuid,
+    pub unpinned_by: Uuid,
+}
@@ -0,0 +1,23 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Presence of an user in the `Hub`, an user is online while at least one
+/// of the user's connections is open
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Presence {
+    pub user_id: Uuid,
+    pub online: bool,
+    /// Last time the user connected to or disconnected from the `Hub`
+    pub last_seen_at: Option<DateTime<Utc>>,
+}
+
+impl Presence {
+    pub fn new(user_id: Uuid, online: bool, last_seen_at: Option<DateTime<Utc>>) -> Self {
+        Self {
+            user_id,
+            online,
+            last_seen_at,
+        }
+    }
+}
@@ -1,6 +1,108 @@
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
-use crate::domain::chat::InputProtoMessageDTO;
+use crate::domain::chat::{
+    InputProtoDeleteMessageDTO, InputProtoEditMessageDTO, InputProtoMarkReadDTO,
+    InputProtoMessageDTO, InputProtoReactionDTO, InputProtoResumeDTO, InputProtoTypingDTO,
+};
+
+use super::Proto;
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
-pub struct Input(pub InputProtoMessageDTO);
+#[serde(tag = "type", content = "inner")]
+pub enum Input {
+    #[serde(rename = "message")]
+    Message(InputProtoMessageDTO),
+    #[serde(rename = "resume")]
+    Resume(InputProtoResumeDTO),
+    #[serde(rename = "edit-message")]
+    EditMessage(InputProtoEditMessageDTO),
+    #[serde(rename = "delete-message")]
+    DeleteMessage(InputProtoDeleteMessageDTO),
+    #[serde(rename = "mark-read")]
+    MarkRead(InputProtoMarkReadDTO),
+    #[serde(rename = "typing-started")]
+    TypingStarted(InputProtoTypingDTO),
+    #[serde(rename = "typing-stopped")]
+    TypingStopped(InputProtoTypingDTO),
+    #[serde(rename = "add-reaction")]
+    AddReaction(InputProtoReactionDTO),
+    #[serde(rename = "remove-reaction")]
+    RemoveReaction(InputProtoReactionDTO),
+}
+
+/// Frame read from a client's WebSocket, clients predating the tagged
+/// `Input` frames send the message to store as the frame's content
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+pub enum InputFrame {
+    Tagged(Input),
+    Legacy(InputProtoMessageDTO),
+}
+
+impl From<InputFrame> for Input {
+    fn from(frame: InputFrame) -> Self {
+        match frame {
+            InputFrame::Tagged(input) => input,
+            InputFrame::Legacy(message) => Input::Message(message),
+        }
+    }
+}
+
+/// A `Proto<Input>` received by the `Hub` along with the connection
+/// it was read from
+#[derive(Clone, Debug)]
+pub struct InputEnvelope {
+    pub user_id: Uuid,
+    pub connection_id: Uuid,
+    pub proto: Proto<Input>,
+}
+
+impl InputEnvelope {
+    pub fn new(user_id: Uuid, connection_id: Uuid, proto: Proto<Input>) -> Self {
+        Self {
+            user_id,
+            connection_id,
+            proto,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(frame: &str) -> Input {
+        let proto: Proto<InputFrame> = serde_json::from_str(frame).unwrap();
+
+        proto.inner.into()
+    }
+
+    #[test]
+    fn it_parses_legacy_message_frames() {
+        let chat_id = Uuid::new_v4();
+        let input = parse(&format!(
+            r#"{{"inner":{{"chat_id":"{}","body":"hello"}}}}"#,
+            chat_id
+        ));
+
+        match input {
+            Input::Message(message) => {
+                assert_eq!(message.chat_id, chat_id);
+                assert_eq!(message.body, "hello");
+            }
+            _ => panic!("expected a message, found {:?}", input),
+        }
+    }
+
+    #[test]
+    fn it_parses_tagged_frames() {
+        let chat_id = Uuid::new_v4();
+        let input = parse(&format!(
+            r#"{{"inner":{{"type":"typing-started","inner":{{"chat_id":"{}"}}}}}}"#,
+            chat_id
+        ));
+
+        assert!(matches!(input, Input::TypingStarted(_)));
+    }
+}
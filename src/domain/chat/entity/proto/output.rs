@@ -1,8 +1,13 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::domain::chat::{InputProtoMessageDTO, Message};
+use crate::domain::chat::{
+    Chat, ChatRole, ChatSettings, InputProtoMessageDTO, Message, MessagesExpired, Pin, Presence,
+    Reaction, ReadReceipt, Unpin,
+};
 use crate::domain::user::User;
+use crate::error;
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(tag = "type", content = "inner")]
@@ -21,6 +26,42 @@ pub enum Parcel {
     ForeignMessage(Uuid, InputProtoMessageDTO),
     #[serde(rename = "message")]
     LocalMessage(Message),
+    #[serde(rename = "ack")]
+    Ack(Ack),
+    #[serde(rename = "message-edited")]
+    MessageEdited(Message),
+    #[serde(rename = "message-deleted")]
+    MessageDeleted(Message),
+    #[serde(rename = "chat-updated")]
+    ChatUpdated(Chat),
+    #[serde(rename = "chat-settings-updated")]
+    ChatSettingsUpdated(ChatSettings),
+    #[serde(rename = "participants-added")]
+    ParticipantsAdded(ParticipantsAdded),
+    #[serde(rename = "participant-removed")]
+    ParticipantRemoved(ParticipantRemoved),
+    #[serde(rename = "role-changed")]
+    RoleChanged(RoleChanged),
+    #[serde(rename = "read")]
+    Read(ReadReceipt),
+    #[serde(rename = "typing-started")]
+    TypingStarted(Typing),
+    #[serde(rename = "typing-stopped")]
+    TypingStopped(Typing),
+    #[serde(rename = "message-pinned")]
+    MessagePinned(Pin),
+    #[serde(rename = "message-unpinned")]
+    MessageUnpinned(Unpin),
+    #[serde(rename = "messages-expired")]
+    MessagesExpired(MessagesExpired),
+    #[serde(rename = "mentioned")]
+    Mentioned(Message),
+    #[serde(rename = "reaction")]
+    Reaction(Reaction),
+    #[serde(rename = "online")]
+    Online(Presence),
+    #[serde(rename = "offline")]
+    Offline(Presence),
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
@@ -39,6 +80,61 @@ pub enum Error {
     NotJoined,
     #[serde(rename = "invalid-message-body")]
     InvalidMessageBody,
+    #[serde(rename = "invalid-json")]
+    InvalidJson,
+    #[serde(rename = "chat-not-found")]
+    ChatNotFound,
+    #[serde(rename = "not-participant")]
+    NotParticipant,
+    #[serde(rename = "author-mismatch")]
+    AuthorMismatch,
+    #[serde(rename = "duplicate-client-message-id")]
+    DuplicateClientMessageId,
+    #[serde(rename = "message-not-found")]
+    MessageNotFound,
+    #[serde(rename = "not-message-author")]
+    NotMessageAuthor,
+    #[serde(rename = "not-allowed")]
+    NotAllowed,
+    #[serde(rename = "direct-chat-not-modifiable")]
+    DirectChatNotModifiable,
+    #[serde(rename = "invalid-attachment")]
+    InvalidAttachment,
+    #[serde(rename = "invalid-reaction")]
+    InvalidReaction,
+    #[serde(rename = "invalid-reply")]
+    InvalidReply,
+    #[serde(rename = "storage-failure")]
+    StorageFailure,
+    #[serde(rename = "internal-error")]
+    Internal,
+}
+
+impl From<&error::Error> for Error {
+    fn from(e: &error::Error) -> Self {
+        match e {
+            error::Error::JsonParsingError(_) => Error::InvalidJson,
+            error::Error::ChatNotFound => Error::ChatNotFound,
+            error::Error::UserDoesntBelongToChat(_, _) => Error::NotParticipant,
+            error::Error::ParticipantNotFound(_, _) => Error::NotParticipant,
+            error::Error::AuthorMismatch(_, _) => Error::AuthorMismatch,
+            error::Error::DuplicateClientMessageId(_) => Error::DuplicateClientMessageId,
+            error::Error::ChatActionNotAllowed(_, _, _) => Error::NotAllowed,
+            error::Error::DirectChatNotModifiable(_) => Error::DirectChatNotModifiable,
+            error::Error::MessageNotFound(_) => Error::MessageNotFound,
+            error::Error::NotMessageAuthor(_, _) => Error::NotMessageAuthor,
+            error::Error::MissingAttachment(_)
+            | error::Error::UnexpectedAttachment
+            | error::Error::AttachmentNotFound(_)
+            | error::Error::AttachmentNotImage(_) => Error::InvalidAttachment,
+            error::Error::InvalidReaction(_) => Error::InvalidReaction,
+            error::Error::InvalidReplyTo(_) => Error::InvalidReply,
+            error::Error::DatabaseError(_, _) | error::Error::UnableToStoreMessage => {
+                Error::StorageFailure
+            }
+            _ => Error::Internal,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -53,6 +149,53 @@ pub struct UserJoined {
     pub user: User,
 }
 
+/// Acknowledges a message sent with a `client_message_id` was stored
+#[derive(Debug, Clone, PartialEq, Copy, Serialize, Deserialize)]
+pub struct Ack {
+    pub client_message_id: Uuid,
+    pub message_id: Uuid,
+    pub chat_id: Uuid,
+    pub sequence: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Notifies the participants of a chat that users were added to it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParticipantsAdded {
+    pub chat: Chat,
+    pub participants_ids: Vec<Uuid>,
+    pub added_by: Uuid,
+}
+
+/// Notifies the participants of a chat, and the removed participant, that
+/// an user was removed from the chat. If `removed_by` is the removed
+/// participant then the participant left the chat
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParticipantRemoved {
+    pub chat: Chat,
+    pub participant_id: Uuid,
+    pub removed_by: Uuid,
+}
+
+/// Notifies the participants of a chat that the role of a participant
+/// changed. When the ownership of the chat is transferred, the `chat`
+/// reflects the role of the previous owner as well
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoleChanged {
+    pub chat: Chat,
+    pub participant_id: Uuid,
+    pub role: ChatRole,
+    pub changed_by: Uuid,
+}
+
+/// Notifies the participants of a chat that an user started or stopped
+/// typing
+#[derive(Debug, Clone, PartialEq, Copy, Serialize, Deserialize)]
+pub struct Typing {
+    pub chat_id: Uuid,
+    pub user_id: Uuid,
+}
+
 #[derive(Debug, Clone, PartialEq, Copy, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UserLeft {
@@ -84,8 +227,75 @@ impl UserJoined {
     }
 }
 
+impl Ack {
+    pub fn new(client_message_id: Uuid, message: &Message) -> Self {
+        Ack {
+            client_message_id,
+            message_id: message.id,
+            chat_id: message.chat.id,
+            sequence: message.sequence,
+            created_at: message.created_at,
+        }
+    }
+}
+
+impl ParticipantsAdded {
+    pub fn new(chat: Chat, participants_ids: Vec<Uuid>, added_by: Uuid) -> Self {
+        ParticipantsAdded {
+            chat,
+            participants_ids,
+            added_by,
+        }
+    }
+}
+
+impl ParticipantRemoved {
+    pub fn new(chat: Chat, participant_id: Uuid, removed_by: Uuid) -> Self {
+        ParticipantRemoved {
+            chat,
+            participant_id,
+            removed_by,
+        }
+    }
+}
+
+impl RoleChanged {
+    pub fn new(chat: Chat, participant_id: Uuid, role: ChatRole, changed_by: Uuid) -> Self {
+        RoleChanged {
+            chat,
+            participant_id,
+            role,
+            changed_by,
+        }
+    }
+}
+
+impl Typing {
+    pub fn new(chat_id: Uuid, user_id: Uuid) -> Self {
+        Typing { chat_id, user_id }
+    }
+}
+
 impl UserLeft {
     pub fn new(user_id: Uuid) -> Self {
         UserLeft { user_id }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_serializes_rejected_input_errors_with_stable_codes() {
+        let parcel = Parcel::Error(Error::from(&error::Error::UserDoesntBelongToChat(
+            Uuid::nil(),
+            Uuid::nil(),
+        )));
+
+        assert_eq!(
+            serde_json::to_string(&parcel).unwrap(),
+            r#"{"type":"error","inner":{"code":"not-participant"}}"#
+        );
+    }
+}
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::error::{Error, Result};
+
+/// Max amount of characters of a reaction, enough to fit emoji sequences
+/// such as flags or families
+const MAX_REACTION_LENGTH: usize = 16;
+
+/// Reactions of the same emoji to a `Message`
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ReactionCount {
+    pub emoji: String,
+    pub count: i64,
+    /// IDs of the users who reacted, from the first to the last one
+    pub users_ids: Vec<Uuid>,
+}
+
+/// A reaction added to or removed from a `Message` by an user, along with
+/// the reactions of the message after the change
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Reaction {
+    pub chat_id: Uuid,
+    pub message_id: Uuid,
+    pub user_id: Uuid,
+    pub emoji: String,
+    pub added: bool,
+    pub reactions: Vec<ReactionCount>,
+}
+
+/// Makes sure the provided `emoji` is a valid reaction, reactions are not
+/// empty and have no whitespaces
+pub fn validate_reaction(emoji: &str) -> Result<()> {
+    if emoji.is_empty()
+        || emoji.chars().count() > MAX_REACTION_LENGTH
+        || emoji.chars().any(char::is_whitespace)
+    {
+        return Err(Error::InvalidReaction(emoji.to_string()));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_validates_reactions() {
+        assert!(validate_reaction("👍").is_ok());
+        assert!(validate_reaction("👨‍👩‍👧‍👦").is_ok());
+        assert!(validate_reaction("").is_err());
+        assert!(validate_reaction("👍 👍").is_err());
+        assert!(validate_reaction(&"👍".repeat(MAX_REACTION_LENGTH + 1)).is_err());
+    }
+}
@@ -0,0 +1,114 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use uuid::Uuid;
+
+use super::{Chat, ChatSettings, Message};
+
+/// Position of the last message of a chat read by one of its participants
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ReadReceipt {
+    pub chat_id: Uuid,
+    pub user_id: Uuid,
+    /// Sequence number of the last message read
+    pub sequence: i64,
+    pub read_at: Option<DateTime<Utc>>,
+}
+
+/// A `Chat` as seen by one of its participants, along with the amount of
+/// messages the participant didn't read yet, the last message of the
+/// chat and the participant's settings for the chat
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ChatSummary {
+    #[serde(flatten)]
+    pub chat: Chat,
+    pub last_read_sequence: i64,
+    pub unread_count: i64,
+    pub last_message: Option<Message>,
+    pub settings: ChatSettings,
+    /// Time of the last message of the chat, or the time the participant
+    /// joined the chat if no messages were sent since
+    pub last_activity_at: DateTime<Utc>,
+}
+
+/// Sorts the chats of a participant as listed to the participant, pinned
+/// chats first, then chats with a custom position, then the most recently
+/// active chats
+pub fn sort_chat_summaries(summaries: &mut [ChatSummary]) {
+    summaries.sort_by(|a, b| {
+        b.settings
+            .pinned
+            .cmp(&a.settings.pinned)
+            .then_with(|| match (a.settings.position, b.settings.position) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            })
+            .then_with(|| b.last_activity_at.cmp(&a.last_activity_at))
+            .then_with(|| a.chat.id.cmp(&b.chat.id))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::domain::chat::entity::ChatKind;
+
+    fn summary(
+        pinned: bool,
+        position: Option<i32>,
+        last_activity_at: DateTime<Utc>,
+    ) -> ChatSummary {
+        let chat_id = Uuid::new_v4();
+
+        ChatSummary {
+            chat: Chat {
+                id: chat_id,
+                kind: ChatKind::Group,
+                title: None,
+                description: None,
+                avatar_url: None,
+                message_ttl: None,
+                participants_ids: vec![],
+                roles: Default::default(),
+            },
+            last_read_sequence: 0,
+            unread_count: 0,
+            last_message: None,
+            settings: ChatSettings {
+                chat_id,
+                pinned,
+                position,
+                ..Default::default()
+            },
+            last_activity_at,
+        }
+    }
+
+    #[test]
+    fn it_sorts_pinned_then_positioned_then_active_chats() {
+        let now = Utc::now();
+        let pinned = summary(true, None, now - Duration::days(2));
+        let positioned = summary(false, Some(1), now - Duration::days(3));
+        let first_positioned = summary(false, Some(0), now - Duration::days(4));
+        let recent = summary(false, None, now);
+        let old = summary(false, None, now - Duration::days(1));
+        let mut summaries = vec![
+            old.clone(),
+            positioned.clone(),
+            recent.clone(),
+            pinned.clone(),
+            first_positioned.clone(),
+        ];
+
+        sort_chat_summaries(&mut summaries);
+
+        assert_eq!(
+            summaries,
+            vec![pinned, first_positioned, positioned, recent, old]
+        );
+    }
+}
@@ -0,0 +1,71 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::chat::dto::InputProtoMessageDTO;
+use crate::error::{Error, Result};
+
+use super::MessageKind;
+
+/// Max amount of days a message can be scheduled ahead
+pub const MAX_SCHEDULE_DAYS: i64 = 365;
+
+/// A message kept pending until its `send_at` time is due, then it is
+/// delivered to the chat as if its author sent it.
+///
+/// Scheduled messages are only visible to their author until delivered.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ScheduledMessage {
+    pub id: Uuid,
+    pub chat_id: Uuid,
+    pub author_id: Uuid,
+    pub body: String,
+    pub kind: MessageKind,
+    pub file_id: Option<Uuid>,
+    pub reply_to: Option<Uuid>,
+    pub send_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ScheduledMessage {
+    /// Builds the message delivered once the scheduled message is due, the
+    /// ID of the scheduled message is used as `client_message_id` so the
+    /// message is delivered once
+    pub fn into_input(self) -> InputProtoMessageDTO {
+        InputProtoMessageDTO {
+            author_id: Some(self.author_id),
+            chat_id: self.chat_id,
+            body: self.body,
+            client_message_id: Some(self.id),
+            kind: self.kind,
+            file_id: self.file_id,
+            reply_to: self.reply_to,
+        }
+    }
+}
+
+/// Makes sure the provided `send_at` time is after `now` and within
+/// `MAX_SCHEDULE_DAYS`
+pub fn validate_send_at(send_at: &DateTime<Utc>, now: DateTime<Utc>) -> Result<()> {
+    if *send_at <= now || *send_at > now + Duration::days(MAX_SCHEDULE_DAYS) {
+        return Err(Error::InvalidSendAt(*send_at));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_validates_send_at_times() {
+        let now = Utc::now();
+
+        assert!(validate_send_at(&(now + Duration::minutes(1)), now).is_ok());
+        assert!(validate_send_at(&(now + Duration::days(MAX_SCHEDULE_DAYS)), now).is_ok());
+        assert!(validate_send_at(&now, now).is_err());
+        assert!(validate_send_at(&(now - Duration::minutes(1)), now).is_err());
+        assert!(validate_send_at(&(now + Duration::days(MAX_SCHEDULE_DAYS + 1)), now).is_err());
+    }
+}
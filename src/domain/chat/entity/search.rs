@@ -0,0 +1,122 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::error::{Error, Result};
+
+use super::Message;
+
+/// Max amount of characters of a search query
+const MAX_SEARCH_QUERY_LENGTH: usize = 256;
+
+/// Delimiters of the matching words of a message's headline, control
+/// characters are used given that they can't be mistaken for markup
+pub const HEADLINE_START_SEL: char = '\u{2}';
+pub const HEADLINE_STOP_SEL: char = '\u{3}';
+
+/// Filters of a full-text search over the messages of the chats an user
+/// belongs to.
+///
+/// `query` supports the syntax of web search engines, quoted text for
+/// phrases, `or` for alternatives and `-` to exclude words.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MessagesSearch {
+    pub query: String,
+    pub chat_id: Option<Uuid>,
+    pub author_id: Option<Uuid>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl MessagesSearch {
+    pub fn validate(&self) -> Result<()> {
+        let query = self.query.trim();
+
+        if query.is_empty() || query.chars().count() > MAX_SEARCH_QUERY_LENGTH {
+            return Err(Error::InvalidSearchQuery(format!(
+                "the query must have between 1 and {} characters",
+                MAX_SEARCH_QUERY_LENGTH
+            )));
+        }
+
+        if let (Some(from), Some(to)) = (self.from, self.to) {
+            if from > to {
+                return Err(Error::InvalidSearchQuery(String::from(
+                    "\"from\" must be earlier than \"to\"",
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A message matching a search along with a `snippet` of its body where
+/// the matching words are wrapped in `<mark>` tags, the rest of the snippet
+/// is HTML-escaped
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SearchResult {
+    pub message: Message,
+    pub snippet: String,
+}
+
+impl SearchResult {
+    /// Builds a search result out of the `headline` of the `message`, where
+    /// the matching words are delimited by `HEADLINE_START_SEL` and
+    /// `HEADLINE_STOP_SEL`
+    pub fn new(message: Message, headline: &str) -> Self {
+        Self {
+            message,
+            snippet: make_snippet(headline),
+        }
+    }
+}
+
+/// HTML-escapes the provided `headline` and wraps its matching words in
+/// `<mark>` tags
+fn make_snippet(headline: &str) -> String {
+    let mut snippet = String::with_capacity(headline.len());
+
+    for c in headline.chars() {
+        match c {
+            HEADLINE_START_SEL => snippet.push_str("<mark>"),
+            HEADLINE_STOP_SEL => snippet.push_str("</mark>"),
+            '&' => snippet.push_str("&amp;"),
+            '<' => snippet.push_str("&lt;"),
+            '>' => snippet.push_str("&gt;"),
+            '"' => snippet.push_str("&quot;"),
+            '\'' => snippet.push_str("&#39;"),
+            c => snippet.push(c),
+        }
+    }
+
+    snippet
+}
+
+/// A page of search results sorted from the newest message to the oldest.
+///
+/// `next` is the offset to fetch the next page, `None` if there are no more
+/// results to fetch.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SearchPage {
+    pub results: Vec<SearchResult>,
+    pub next: Option<i64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_escapes_snippets_around_the_matching_words() {
+        let headline = format!(
+            "<img src=x onerror=\"alert('hi')\"> {}hello{} & bye",
+            HEADLINE_START_SEL, HEADLINE_STOP_SEL
+        );
+
+        assert_eq!(
+            make_snippet(&headline),
+            "&lt;img src=x onerror=&quot;alert(&#39;hi&#39;)&quot;&gt; <mark>hello</mark> &amp; bye"
+        );
+    }
+}
@@ -0,0 +1,20 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Preferences of a participant about one of the participant's chats,
+/// settings are only visible to the participant
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ChatSettings {
+    pub chat_id: Uuid,
+    /// Clients are expected to silence the chat until this time, mentions
+    /// are notified anyway
+    pub muted_until: Option<DateTime<Utc>>,
+    /// Archived chats are listed apart from the rest of chats
+    pub archived: bool,
+    /// Pinned chats are listed before the rest of chats
+    pub pinned: bool,
+    /// Custom position of the chat in the participant's chat list, chats
+    /// with a lower position are listed first
+    pub position: Option<i32>,
+}
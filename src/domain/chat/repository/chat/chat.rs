@@ -1,13 +1,14 @@
+use chrono::{DateTime, Utc};
 use futures::TryStreamExt;
 use sqlx::Row;
-use std::str::FromStr;
 use uuid::Uuid;
 
-use crate::domain::chat::entity::{Chat, Message};
+use crate::domain::chat::dto::{ChatDetailsDTO, ChatSettingsDTO};
+use crate::domain::chat::entity::{Chat, ChatKind, ChatRole, ChatSettings, ReadReceipt};
 use crate::error::{Error, Result};
 use crate::infrastructure::database::DbPool;
 
-use super::dto::{ChatDTO, ChatsUsersDTO};
+use super::dto::{ChatDTO, ChatParticipantsDTO, ChatSettingsRowDTO, ChatsUsersDTO, ReadStateDTO};
 
 pub struct ChatRepository {
     db_pool: &'static DbPool,
@@ -18,13 +19,23 @@ impl ChatRepository {
         Self { db_pool }
     }
 
-    pub async fn create(&self, participants_ids: Vec<Uuid>) -> Result<Chat> {
+    /// Creates a chat for the provided participants, the user with the
+    /// provided `owner_id` becomes the owner of the chat
+    pub async fn create(
+        &self,
+        owner_id: &Uuid,
+        participants_ids: Vec<Uuid>,
+        details: &ChatDetailsDTO,
+    ) -> Result<Chat> {
         let mut tx = self.db_pool.begin().await?;
 
-        let chat: ChatDTO =
-            sqlx::query_as("INSERT INTO chats (id) VALUES (uuid_generate_v4()) RETURNING *")
-                .fetch_one(&mut tx)
-                .await?;
+        let chat: ChatDTO = sqlx::query_as(
+            "INSERT INTO chats (id, title, description) VALUES (uuid_generate_v4(), $1, $2) RETURNING *",
+        )
+        .bind(details.title.clone().flatten())
+        .bind(details.description.clone().flatten())
+        .fetch_one(&mut tx)
+        .await?;
 
         let _: ChatsUsersDTO = sqlx::query_as(
             ChatRepository::make_insert_chats_users_query(&chat.id, &participants_ids).as_str(),
@@ -32,61 +43,464 @@ impl ChatRepository {
         .fetch_one(&mut tx)
         .await?;
 
+        sqlx::query("UPDATE chats_users SET role = $3 WHERE chat_id = $1 AND user_id = $2")
+            .bind(chat.id)
+            .bind(owner_id)
+            .bind(ChatRole::Owner.to_string())
+            .execute(&mut tx)
+            .await?;
+
         tx.commit().await?;
 
         Ok(Chat {
             id: chat.id,
+            kind: ChatKind::Group,
+            title: chat.title,
+            description: chat.description,
+            avatar_url: None,
+            message_ttl: None,
+            roles: participants_ids
+                .iter()
+                .map(|participant_id| {
+                    if participant_id == owner_id {
+                        (*participant_id, ChatRole::Owner)
+                    } else {
+                        (*participant_id, ChatRole::Member)
+                    }
+                })
+                .collect(),
             participants_ids,
         })
     }
 
+    /// Finds the direct chat between the provided users
+    pub async fn find_direct(&self, user_id: &Uuid, other_user_id: &Uuid) -> Result<Option<Chat>> {
+        let (first_user_id, second_user_id) = sort_pair(user_id, other_user_id);
+        let row = sqlx::query(
+            "SELECT chat_id FROM direct_chats WHERE first_user_id = $1 AND second_user_id = $2",
+        )
+        .bind(first_user_id)
+        .bind(second_user_id)
+        .fetch_optional(self.db_pool)
+        .await?;
+
+        match row {
+            Some(row) => {
+                let chat_id: Uuid = row.try_get("chat_id")?;
+
+                Ok(Some(self.find_by_id(&chat_id).await?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Creates a direct chat between the provided users, retrieving `None`
+    /// if the users have a direct chat already
+    pub async fn create_direct(
+        &self,
+        user_id: &Uuid,
+        other_user_id: &Uuid,
+    ) -> Result<Option<Chat>> {
+        let (first_user_id, second_user_id) = sort_pair(user_id, other_user_id);
+        let mut tx = self.db_pool.begin().await?;
+
+        let chat: ChatDTO = sqlx::query_as(
+            "INSERT INTO chats (id, kind) VALUES (uuid_generate_v4(), $1) RETURNING *",
+        )
+        .bind(ChatKind::Direct.to_string())
+        .fetch_one(&mut tx)
+        .await?;
+
+        let pair = sqlx::query(
+            r#"
+            INSERT INTO direct_chats (chat_id, first_user_id, second_user_id)
+                VALUES ($1, $2, $3)
+            ON CONFLICT (first_user_id, second_user_id)
+                DO NOTHING
+            RETURNING
+                chat_id"#,
+        )
+        .bind(chat.id)
+        .bind(first_user_id)
+        .bind(second_user_id)
+        .fetch_optional(&mut tx)
+        .await?;
+
+        if pair.is_none() {
+            tx.rollback().await?;
+
+            return Ok(None);
+        }
+
+        let participants_ids = vec![*user_id, *other_user_id];
+        let _: ChatsUsersDTO = sqlx::query_as(
+            ChatRepository::make_insert_chats_users_query(&chat.id, &participants_ids).as_str(),
+        )
+        .fetch_one(&mut tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(Chat {
+            id: chat.id,
+            kind: ChatKind::Direct,
+            title: None,
+            description: None,
+            avatar_url: None,
+            message_ttl: None,
+            roles: participants_ids
+                .iter()
+                .map(|participant_id| (*participant_id, ChatRole::Member))
+                .collect(),
+            participants_ids,
+        }))
+    }
+
     pub async fn find_by_id(&self, id: &Uuid) -> Result<Chat> {
-        let mut participants_ids: Vec<Uuid> = Vec::new();
-        let mut rows = sqlx::query(
+        let chat = sqlx::query_file_as!(ChatParticipantsDTO, "sql/find_chat.sql", id)
+            .fetch_optional(self.db_pool)
+            .await?;
+
+        match chat {
+            Some(chat) => Ok(chat.into_chat()),
+            None => Err(Error::ChatNotFound),
+        }
+    }
+
+    pub async fn fetch_user_chats(&self, user_id: &Uuid) -> Result<Vec<Chat>> {
+        let chats = sqlx::query_file_as!(ChatParticipantsDTO, "sql/fetch_user_chats.sql", user_id)
+            .fetch_all(self.db_pool)
+            .await?
+            .into_iter()
+            .map(|chat| chat.into_chat())
+            .collect();
+
+        Ok(chats)
+    }
+
+    /// Fetches the read state of the user with the provided `user_id` in
+    /// each of the user's chats
+    pub async fn fetch_read_states(&self, user_id: &Uuid) -> Result<Vec<ReadStateDTO>> {
+        let read_states = sqlx::query_file_as!(ReadStateDTO, "sql/fetch_read_states.sql", user_id)
+            .fetch_all(self.db_pool)
+            .await?;
+
+        Ok(read_states)
+    }
+
+    /// Moves the last read message pointer of a participant forward up to
+    /// the message with the provided `sequence`, the pointer never goes
+    /// back nor beyond the last message of the chat.
+    ///
+    /// The returned flag is `true` if the pointer was moved
+    pub async fn mark_read(
+        &self,
+        chat_id: &Uuid,
+        user_id: &Uuid,
+        sequence: i64,
+    ) -> Result<(ReadReceipt, bool)> {
+        let row = sqlx::query(
             r#"
+            WITH updated AS (
+                UPDATE
+                    chats_users
+                SET
+                    last_read_sequence = LEAST($3, chats.last_sequence),
+                    last_read_at = CURRENT_TIMESTAMP
+                FROM
+                    chats
+                WHERE
+                    chats.id = chats_users.chat_id
+                    AND chats_users.chat_id = $1
+                    AND chats_users.user_id = $2
+                    AND chats_users.last_read_sequence < LEAST($3, chats.last_sequence)
+                RETURNING
+                    chats_users.last_read_sequence,
+                    chats_users.last_read_at
+            )
             SELECT
-                chats_users.user_id
+                last_read_sequence,
+                last_read_at,
+                TRUE AS updated
             FROM
+                updated
+            UNION ALL
+            SELECT
+                last_read_sequence,
+                last_read_at,
+                FALSE AS updated
+            FROM
+                chats_users
+            WHERE
+                chat_id = $1
+                AND user_id = $2
+                AND NOT EXISTS (SELECT 1 FROM updated)"#,
+        )
+        .bind(chat_id)
+        .bind(user_id)
+        .bind(sequence)
+        .fetch_optional(self.db_pool)
+        .await?;
+
+        match row {
+            Some(row) => Ok((
+                ReadReceipt {
+                    chat_id: *chat_id,
+                    user_id: *user_id,
+                    sequence: row.try_get("last_read_sequence")?,
+                    read_at: row.try_get("last_read_at")?,
+                },
+                row.try_get("updated")?,
+            )),
+            None => Err(Error::ParticipantNotFound(*user_id, *chat_id)),
+        }
+    }
+
+    /// Fetches the settings of the user with the provided `user_id` for
+    /// each of the user's chats, along with the time the user joined the chat
+    pub async fn fetch_settings(
+        &self,
+        user_id: &Uuid,
+    ) -> Result<Vec<(ChatSettings, DateTime<Utc>)>> {
+        let settings: Vec<ChatSettingsRowDTO> = sqlx::query_as(
+            r#"
+            SELECT
+                chat_id,
+                muted_until,
+                archived,
+                pinned,
+                position,
+                created_at AS joined_at
+            FROM
+                chats_users
+            WHERE
+                user_id = $1"#,
+        )
+        .bind(user_id)
+        .fetch_all(self.db_pool)
+        .await?;
+
+        Ok(settings
+            .into_iter()
+            .map(|settings| {
+                let joined_at = settings.joined_at;
+
+                (settings.into_settings(), joined_at)
+            })
+            .collect())
+    }
+
+    /// Updates the settings of the participant with the provided `user_id`
+    /// for the chat with the provided `chat_id`
+    pub async fn update_settings(
+        &self,
+        chat_id: &Uuid,
+        user_id: &Uuid,
+        settings: &ChatSettingsDTO,
+    ) -> Result<ChatSettings> {
+        let settings: Option<ChatSettingsRowDTO> = sqlx::query_as(
+            r#"
+            UPDATE
+                chats_users
+            SET
+                muted_until = CASE WHEN $3 THEN $4 ELSE muted_until END,
+                archived = COALESCE($5, archived),
+                pinned = COALESCE($6, pinned),
+                position = CASE WHEN $7 THEN $8 ELSE position END,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE
+                chat_id = $1
+                AND user_id = $2
+            RETURNING
+                chat_id,
+                muted_until,
+                archived,
+                pinned,
+                position,
+                created_at AS joined_at"#,
+        )
+        .bind(chat_id)
+        .bind(user_id)
+        .bind(settings.muted_until.is_some())
+        .bind(settings.muted_until.flatten())
+        .bind(settings.archived)
+        .bind(settings.pinned)
+        .bind(settings.position.is_some())
+        .bind(settings.position.flatten())
+        .fetch_optional(self.db_pool)
+        .await?;
+
+        settings
+            .map(ChatSettingsRowDTO::into_settings)
+            .ok_or(Error::ParticipantNotFound(*user_id, *chat_id))
+    }
+
+    /// Updates the `details` of the chat with the provided `chat_id`
+    pub async fn update_details(&self, chat_id: &Uuid, details: &ChatDetailsDTO) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE
                 chats
-                INNER JOIN chats_users ON chats_users.chat_id = $1
+            SET
+                title = CASE WHEN $2 THEN $3 ELSE title END,
+                description = CASE WHEN $4 THEN $5 ELSE description END,
+                updated_at = CURRENT_TIMESTAMP
             WHERE
-                chats.id = $1"#,
+                id = $1"#,
         )
-        .bind(id)
+        .bind(chat_id)
+        .bind(details.title.is_some())
+        .bind(details.title.clone().flatten())
+        .bind(details.description.is_some())
+        .bind(details.description.clone().flatten())
+        .execute(self.db_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Sets the `message_ttl` of the chat with the provided `chat_id`
+    pub async fn update_message_ttl(&self, chat_id: &Uuid, message_ttl: Option<i32>) -> Result<()> {
+        sqlx::query(
+            "UPDATE chats SET message_ttl = $2, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+        )
+        .bind(chat_id)
+        .bind(message_ttl)
+        .execute(self.db_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Replaces the avatar of the chat with the provided `chat_id`
+    pub async fn update_avatar(&self, chat_id: &Uuid, avatar_id: &Uuid) -> Result<()> {
+        sqlx::query(
+            "UPDATE chats SET avatar_id = $2, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+        )
+        .bind(chat_id)
+        .bind(avatar_id)
+        .execute(self.db_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Adds the existing users with the provided `participants_ids` to the
+    /// chat, retrieving the IDs of the users which were not participants
+    /// of the chat already
+    pub async fn add_participants(
+        &self,
+        chat_id: &Uuid,
+        participants_ids: &[Uuid],
+    ) -> Result<Vec<Uuid>> {
+        let mut added_ids: Vec<Uuid> = Vec::new();
+        let mut rows = sqlx::query(
+            r#"
+            INSERT INTO chats_users (chat_id, user_id)
+            SELECT
+                $1,
+                users.id
+            FROM
+                users
+            WHERE
+                users.id = ANY($2)
+            ON CONFLICT (chat_id, user_id)
+                DO NOTHING
+            RETURNING
+                user_id"#,
+        )
+        .bind(chat_id)
+        .bind(participants_ids)
         .fetch(self.db_pool);
 
         while let Some(row) = rows.try_next().await? {
-            let pid: Uuid = row.try_get("user_id")?;
+            added_ids.push(row.try_get("user_id")?);
+        }
+
+        Ok(added_ids)
+    }
 
-            participants_ids.push(pid);
+    /// Removes the user with the provided `participant_id` from the chat,
+    /// retrieving `false` if the user was not a participant of the chat.
+    ///
+    /// If the owner of the chat is removed, the oldest admin of the chat,
+    /// or the oldest participant if there are no admins, becomes the owner
+    pub async fn remove_participant(&self, chat_id: &Uuid, participant_id: &Uuid) -> Result<bool> {
+        let mut tx = self.db_pool.begin().await?;
+        let removed = sqlx::query(
+            "DELETE FROM chats_users WHERE chat_id = $1 AND user_id = $2 RETURNING role",
+        )
+        .bind(chat_id)
+        .bind(participant_id)
+        .fetch_optional(&mut tx)
+        .await?;
+        let removed_role: String = match removed {
+            Some(row) => row.try_get("role")?,
+            None => return Ok(false),
+        };
+
+        if removed_role == ChatRole::Owner.to_string() {
+            sqlx::query(
+                r#"
+                UPDATE
+                    chats_users
+                SET
+                    role = 'owner',
+                    updated_at = CURRENT_TIMESTAMP
+                WHERE
+                    id = (
+                        SELECT
+                            id FROM chats_users
+                        WHERE
+                            chat_id = $1
+                        ORDER BY
+                            role = 'admin' DESC,
+                            created_at ASC
+                        LIMIT 1)"#,
+            )
+            .bind(chat_id)
+            .execute(&mut tx)
+            .await?;
         }
 
-        Ok(Chat {
-            id: id.clone(),
-            participants_ids,
-        })
+        tx.commit().await?;
+
+        Ok(true)
     }
 
-    pub async fn fetch_user_chats(&self, user_id: &Uuid) -> Result<Vec<Chat>> {
-        let mut chats: Vec<Chat> = Vec::new();
-        let mut rows = sqlx::query_file!("sql/fetch_user_chats.sql", user_id).fetch(self.db_pool);
+    /// Updates the role of a participant of the chat. When a participant
+    /// becomes the owner of the chat, the current owner becomes an admin
+    pub async fn update_role(
+        &self,
+        chat_id: &Uuid,
+        participant_id: &Uuid,
+        role: ChatRole,
+    ) -> Result<()> {
+        let mut tx = self.db_pool.begin().await?;
 
-        while let Some(row) = rows.try_next().await? {
-            let participants_ids: String = row.users.unwrap();
-            let participants_ids: Vec<Uuid> = participants_ids
-                .split(',')
-                .into_iter()
-                .map(|uid| Uuid::from_str(uid).unwrap())
-                .collect();
-            let chat_id: Uuid = row.chat_id;
-
-            chats.push(Chat {
-                id: chat_id,
-                participants_ids,
-            });
+        if role == ChatRole::Owner {
+            sqlx::query(
+                "UPDATE chats_users SET role = $2, updated_at = CURRENT_TIMESTAMP WHERE chat_id = $1 AND role = $3",
+            )
+            .bind(chat_id)
+            .bind(ChatRole::Admin.to_string())
+            .bind(ChatRole::Owner.to_string())
+            .execute(&mut tx)
+            .await?;
         }
 
-        Ok(chats)
+        sqlx::query(
+            "UPDATE chats_users SET role = $3, updated_at = CURRENT_TIMESTAMP WHERE chat_id = $1 AND user_id = $2",
+        )
+        .bind(chat_id)
+        .bind(participant_id)
+        .bind(role.to_string())
+        .execute(&mut tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
     }
 
     /// Creates a SQL query to insert multiple relationships of
@@ -120,6 +534,15 @@ impl ChatRepository {
     }
 }
 
+/// Sorts a pair of users IDs as stored in the `direct_chats` table
+fn sort_pair<'a>(user_id: &'a Uuid, other_user_id: &'a Uuid) -> (&'a Uuid, &'a Uuid) {
+    if user_id < other_user_id {
+        (user_id, other_user_id)
+    } else {
+        (other_user_id, user_id)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
@@ -1,10 +1,61 @@
 use chrono::{DateTime, Utc};
 use sqlx::FromRow;
+use std::str::FromStr;
 use uuid::Uuid;
 
+use crate::domain::chat::entity::{Chat, ChatKind, ChatRole};
+
 #[derive(Debug, FromRow)]
 pub struct ChatDTO {
     pub id: Uuid,
+    pub title: Option<String>,
+    pub description: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
+
+/// A row of the `chats` table joined with the chat's avatar URL and the
+/// IDs and roles of the chat's participants
+#[derive(Debug, FromRow)]
+pub struct ChatParticipantsDTO {
+    pub chat_id: Uuid,
+    pub chat_kind: String,
+    pub chat_title: Option<String>,
+    pub chat_description: Option<String>,
+    pub chat_avatar_url: Option<String>,
+    pub chat_message_ttl: Option<i32>,
+    pub participants_ids: Vec<Uuid>,
+    pub participants_roles: Vec<String>,
+}
+
+impl ChatParticipantsDTO {
+    pub fn into_chat(self) -> Chat {
+        Chat {
+            id: self.chat_id,
+            kind: ChatKind::from_str(&self.chat_kind).unwrap_or_default(),
+            title: self.chat_title,
+            description: self.chat_description,
+            avatar_url: self.chat_avatar_url,
+            message_ttl: self.chat_message_ttl,
+            roles: self
+                .participants_ids
+                .iter()
+                .copied()
+                .zip(
+                    self.participants_roles
+                        .iter()
+                        .map(|role| ChatRole::from_str(role).unwrap_or_default()),
+                )
+                .collect(),
+            participants_ids: self.participants_ids,
+        }
+    }
+}
+
+/// Read state of a participant in one of the participant's chats
+#[derive(Debug, FromRow)]
+pub struct ReadStateDTO {
+    pub chat_id: Uuid,
+    pub last_read_sequence: i64,
+    pub unread_count: i64,
+}
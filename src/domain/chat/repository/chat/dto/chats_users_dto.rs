@@ -2,6 +2,8 @@ use chrono::{DateTime, Utc};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use crate::domain::chat::entity::ChatSettings;
+
 #[derive(Debug, FromRow)]
 pub struct ChatsUsersDTO {
     pub id: Uuid,
@@ -10,3 +12,27 @@ pub struct ChatsUsersDTO {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
+
+/// Settings of a participant for one of the participant's chats, along
+/// with the time the participant joined the chat
+#[derive(Debug, FromRow)]
+pub struct ChatSettingsRowDTO {
+    pub chat_id: Uuid,
+    pub muted_until: Option<DateTime<Utc>>,
+    pub archived: bool,
+    pub pinned: bool,
+    pub position: Option<i32>,
+    pub joined_at: DateTime<Utc>,
+}
+
+impl ChatSettingsRowDTO {
+    pub fn into_settings(self) -> ChatSettings {
+        ChatSettings {
+            chat_id: self.chat_id,
+            muted_until: self.muted_until,
+            archived: self.archived,
+            pinned: self.pinned,
+            position: self.position,
+        }
+    }
+}
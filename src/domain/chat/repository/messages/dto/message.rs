@@ -1,15 +1,144 @@
 use chrono::{DateTime, Utc};
 use sqlx::FromRow;
+use std::str::FromStr;
 use uuid::Uuid;
 
+use crate::domain::chat::entity::{Attachment, Chat, Forward, Message, MessageKind, Quote};
+use crate::domain::user::User;
+
+/// A row of the `messages` table joined with the message's author and
+/// the message's attached file if any
 #[derive(Debug, FromRow)]
 pub struct MessageDTO {
-    pub id: Uuid,
-    pub content: String,
-    pub kind: String,
+    pub message_id: Uuid,
+    pub message_content: String,
+    pub message_sequence: i64,
+    pub message_created_at: DateTime<Utc>,
+    pub message_edited_at: Option<DateTime<Utc>>,
+    pub message_deleted_at: Option<DateTime<Utc>>,
+    pub message_kind: String,
+    pub message_reply_to_id: Option<Uuid>,
+    pub message_expires_at: Option<DateTime<Utc>>,
+    pub file_id: Option<Uuid>,
+    pub file_filename: Option<String>,
+    pub file_mime: Option<String>,
+    pub file_size: Option<i32>,
+    pub file_url: Option<String>,
     pub author_id: Uuid,
+    pub author_name: String,
     pub chat_id: Uuid,
-    pub file_id: Uuid,
-    pub created_at: DateTime<Utc>,
-    pub updated_at: DateTime<Utc>,
+}
+
+impl MessageDTO {
+    pub fn into_message(self, chat: Chat) -> Message {
+        let attachment = match (
+            self.file_id,
+            self.file_filename,
+            self.file_mime,
+            self.file_size,
+            self.file_url,
+        ) {
+            (Some(id), Some(filename), Some(mime), Some(size), Some(url)) => Some(AttachmentDTO {
+                id,
+                filename,
+                mime,
+                size,
+                url,
+            }),
+            _ => None,
+        };
+
+        Message {
+            id: self.message_id,
+            body: self.message_content,
+            kind: MessageKind::from_str(&self.message_kind).unwrap_or_default(),
+            attachment: attachment.map(AttachmentDTO::into_attachment),
+            reactions: Vec::new(),
+            mentions: Vec::new(),
+            reply_to: self.message_reply_to_id,
+            quote: None,
+            forwarded_from: None,
+            chat,
+            author: User {
+                id: self.author_id,
+                name: self.author_name,
+            },
+            sequence: self.message_sequence,
+            created_at: self.message_created_at,
+            edited_at: self.message_edited_at,
+            deleted_at: self.message_deleted_at,
+            expires_at: self.message_expires_at,
+        }
+    }
+}
+
+/// A row of the `files` table without the file's bytes
+#[derive(Debug, FromRow)]
+pub struct AttachmentDTO {
+    pub id: Uuid,
+    pub filename: String,
+    pub mime: String,
+    pub size: i32,
+    pub url: String,
+}
+
+impl AttachmentDTO {
+    pub fn into_attachment(self) -> Attachment {
+        Attachment {
+            id: self.id,
+            filename: self.filename,
+            mime: self.mime,
+            size: self.size,
+            url: self.url,
+        }
+    }
+}
+
+/// A replied message joined with its author
+#[derive(Debug, FromRow)]
+pub struct QuoteDTO {
+    pub message_id: Uuid,
+    pub message_content: String,
+    pub message_kind: String,
+    pub message_deleted_at: Option<DateTime<Utc>>,
+    pub author_id: Uuid,
+    pub author_name: String,
+}
+
+impl QuoteDTO {
+    pub fn into_quote(self) -> Quote {
+        Quote::new(
+            self.message_id,
+            User {
+                id: self.author_id,
+                name: self.author_name,
+            },
+            &self.message_content,
+            MessageKind::from_str(&self.message_kind).unwrap_or_default(),
+            self.message_deleted_at.is_some(),
+        )
+    }
+}
+
+/// Provenance of a forwarded message joined with the original author
+#[derive(Debug, FromRow)]
+pub struct ForwardDTO {
+    pub message_id: Uuid,
+    pub forwarded_from_id: Option<Uuid>,
+    pub forwarded_chat_id: Uuid,
+    pub author_id: Uuid,
+    pub author_name: String,
+}
+
+impl ForwardDTO {
+    pub fn into_forward(self) -> Forward {
+        Forward {
+            message_id: self.forwarded_from_id,
+            chat_id: self.forwarded_chat_id,
+            author: User {
+                id: self.author_id,
+                name: self.author_name,
+            },
+        }
+    }
 }
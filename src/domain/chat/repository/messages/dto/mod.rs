@@ -1,3 +1,7 @@
 mod message;
+mod reaction;
+mod scheduled_message;
 
 pub use message::*;
+pub use reaction::*;
+pub use scheduled_message::*;
@@ -0,0 +1,23 @@
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::domain::chat::entity::ReactionCount;
+
+/// Reactions of the same emoji to a message
+#[derive(Debug, FromRow)]
+pub struct ReactionDTO {
+    pub message_id: Uuid,
+    pub emoji: String,
+    pub count: i64,
+    pub users_ids: Vec<Uuid>,
+}
+
+impl ReactionDTO {
+    pub fn into_reaction_count(self) -> ReactionCount {
+        ReactionCount {
+            emoji: self.emoji,
+            count: self.count,
+            users_ids: self.users_ids,
+        }
+    }
+}
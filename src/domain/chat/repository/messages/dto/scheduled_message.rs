@@ -0,0 +1,36 @@
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::domain::chat::entity::{MessageKind, ScheduledMessage};
+
+/// A row of the `scheduled_messages` table
+#[derive(Debug, FromRow)]
+pub struct ScheduledMessageDTO {
+    pub id: Uuid,
+    pub content: String,
+    pub kind: String,
+    pub author_id: Uuid,
+    pub chat_id: Uuid,
+    pub file_id: Option<Uuid>,
+    pub reply_to_id: Option<Uuid>,
+    pub send_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ScheduledMessageDTO {
+    pub fn into_scheduled_message(self) -> ScheduledMessage {
+        ScheduledMessage {
+            id: self.id,
+            chat_id: self.chat_id,
+            author_id: self.author_id,
+            body: self.content,
+            kind: MessageKind::from_str(&self.kind).unwrap_or_default(),
+            file_id: self.file_id,
+            reply_to: self.reply_to_id,
+            send_at: self.send_at,
+            created_at: self.created_at,
+        }
+    }
+}
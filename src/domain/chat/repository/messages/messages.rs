@@ -1,14 +1,20 @@
 use chrono::{DateTime, Utc};
-use futures::TryStreamExt;
-use sqlx::Row;
+use sqlx::{Done, FromRow, Row};
+use std::collections::HashMap;
 use uuid::Uuid;
 
 use crate::domain::chat::dto::InputProtoMessageDTO;
-use crate::domain::chat::entity::{Chat, Message};
-use crate::domain::user::User;
+use crate::domain::chat::entity::{
+    Attachment, Chat, Forward, Mention, Message, MessagesExpired, MessagesSearch, Pin, Quote,
+    ReactionCount, ScheduledMessage, SearchResult, HEADLINE_START_SEL, HEADLINE_STOP_SEL,
+};
 use crate::error::{Error, Result};
 use crate::infrastructure::database::DbPool;
 
+use super::dto::{
+    AttachmentDTO, ForwardDTO, MessageDTO, QuoteDTO, ReactionDTO, ScheduledMessageDTO,
+};
+
 pub struct MessagesRepository {
     db_pool: &'static DbPool,
 }
@@ -18,86 +24,1354 @@ impl MessagesRepository {
         Self { db_pool }
     }
 
+    /// Stores a new message in the provided `chat`.
+    ///
+    /// Every message gets the next sequence number of its chat, sequence
+    /// numbers are monotonically increasing but not necessarily contiguous.
+    ///
+    /// Messages with a `client_message_id` are stored once per author,
+    /// storing the same message again retrieves the existing message. A
+    /// `client_message_id` already used on another chat is rejected.
+    /// The returned flag is `true` if the message was already stored, the
+    /// provided `mentions` are only stored along with new messages.
+    ///
+    /// Forwarded messages are stored along with the provenance provided on
+    /// `forwarded_from`.
+    ///
+    /// Messages stored in a chat with a `message_ttl` expire once the TTL
+    /// elapses, changing the TTL of a chat doesn't affect stored messages.
+    /// Messages with an `expires_at` expire then at the latest, regardless
+    /// of the chat's TTL.
     pub async fn create(
         &self,
         chat: Chat,
+        author_id: &Uuid,
         input_proto_message: InputProtoMessageDTO,
-    ) -> Result<Message> {
-        let mut rows = sqlx::query(
+        mentions: &[Mention],
+        forwarded_from: Option<&Forward>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<(Message, bool)> {
+        let (mentioned_ids, offsets, lengths) = unzip_mentions(mentions);
+        let client_message_id = input_proto_message.client_message_id;
+        let row = sqlx::query(
             r#"
-            WITH message AS (
-                INSERT INTO messages (content,
-                        kind,
-                        author_id,
-                        chat_id)
-                        VALUES($1,
-                            $2,
-                            $3,
-                            $4)
+            WITH sequence AS (
+                UPDATE
+                    chats
+                SET
+                    last_sequence = last_sequence + 1
+                WHERE
+                    chats.id = $4
+                    AND NOT EXISTS (
+                        SELECT
+                            1
+                        FROM
+                            messages
+                        WHERE
+                            messages.author_id = $3
+                            AND messages.client_message_id = $5)
+                    RETURNING
+                        last_sequence,
+                        message_ttl
+                ),
+                inserted AS (
+                    INSERT INTO messages (content,
+                            kind,
+                            author_id,
+                            chat_id,
+                            client_message_id,
+                            file_id,
+                            reply_to_id,
+                            sequence,
+                            expires_at,
+                            forwarded_from_id,
+                            forwarded_author_id,
+                            forwarded_chat_id)
+                    SELECT
+                        $1,
+                        $2,
+                        $3,
+                        $4,
+                        $5,
+                        $6,
+                        $7,
+                        sequence.last_sequence,
+                        LEAST(CURRENT_TIMESTAMP + sequence.message_ttl * INTERVAL '1 second', $14),
+                        $11,
+                        $12,
+                        $13
+                    FROM
+                        sequence
+                    ON CONFLICT (author_id, client_message_id) DO NOTHING
                     RETURNING
                         *
+                ),
+                mentioned AS (
+                    INSERT INTO message_mentions (message_id, user_id, "offset", length)
+                    SELECT
+                        inserted.id,
+                        mention.user_id,
+                        mention.offset,
+                        mention.length
+                    FROM
+                        inserted
+                        CROSS JOIN UNNEST($8::UUID[], $9::INTEGER[], $10::INTEGER[]) AS mention (user_id, "offset", length)
+                ),
+                message AS (
+                    SELECT
+                        inserted.*,
+                        FALSE AS duplicated
+                    FROM
+                        inserted
+                    UNION ALL
+                    SELECT
+                        messages.*,
+                        TRUE AS duplicated
+                    FROM
+                        messages
+                    WHERE
+                        messages.author_id = $3
+                        AND messages.chat_id = $4
+                        AND messages.client_message_id = $5
+                        AND NOT EXISTS (SELECT 1 FROM inserted)
                 )
                 SELECT
                     message.id AS message_id,
                     message.content AS message_content,
+                    message.sequence AS message_sequence,
                     message.created_at AS message_created_at,
+                    message.edited_at AS message_edited_at,
+                    message.deleted_at AS message_deleted_at,
+                    message.kind AS message_kind,
+                    message.reply_to_id AS message_reply_to_id,
+                    message.expires_at AS message_expires_at,
+                    files.id AS file_id,
+                    files.filename AS file_filename,
+                    files.mime AS file_mime,
+                    files.size AS file_size,
+                    files.url AS file_url,
+                    message.duplicated AS message_duplicated,
                     users.id AS author_id,
-                    users. "name" AS author_name
+                    users. "name" AS author_name,
+                    message.chat_id AS chat_id
                 FROM
                     message
-                    INNER JOIN chats ON chats.id = $4
-                    INNER JOIN users ON users.id = $3;
+                    INNER JOIN users ON users.id = message.author_id
+                    LEFT JOIN files ON files.id = message.file_id;
                 "#,
         )
         .bind(input_proto_message.body)
-        .bind("text")
-        .bind(input_proto_message.author_id)
+        .bind(input_proto_message.kind.to_string())
+        .bind(author_id)
+        .bind(input_proto_message.chat_id)
+        .bind(input_proto_message.client_message_id)
+        .bind(input_proto_message.file_id)
+        .bind(input_proto_message.reply_to)
+        .bind(&mentioned_ids)
+        .bind(&offsets)
+        .bind(&lengths)
+        .bind(forwarded_from.and_then(|forward| forward.message_id))
+        .bind(forwarded_from.map(|forward| forward.author.id))
+        .bind(forwarded_from.map(|forward| forward.chat_id))
+        .bind(expires_at)
+        .fetch_optional(self.db_pool)
+        .await?;
+        let row = match (row, client_message_id) {
+            (Some(row), _) => row,
+            (None, Some(client_message_id)) => {
+                return self
+                    .find_duplicate(&chat, author_id, &client_message_id)
+                    .await
+            }
+            (None, None) => return Err(Error::UnableToStoreMessage),
+        };
+        let message_duplicated: bool = row.try_get("message_duplicated")?;
+        let message = MessageDTO::from_row(&row)?;
+        let mut messages = self.with_details(vec![message.into_message(chat)]).await?;
+
+        Ok((messages.remove(0), message_duplicated))
+    }
+
+    /// Fetches up to `limit` messages of the provided `chat` with a sequence
+    /// number lower than `before`, or the latest messages of the chat if
+    /// `before` is not provided, sorted by sequence number
+    pub async fn fetch_chat_messages(
+        &self,
+        chat: &Chat,
+        before: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<Message>> {
+        let messages: Vec<Message> = sqlx::query_file_as!(
+            MessageDTO,
+            "sql/fetch_chat_messages.sql",
+            chat.id,
+            before,
+            limit
+        )
+        .fetch_all(self.db_pool)
+        .await?
+        .into_iter()
+        .map(|message| message.into_message(chat.clone()))
+        .collect();
+
+        let mut messages = self.with_details(messages).await?;
+
+        messages.reverse();
+
+        Ok(messages)
+    }
+
+    /// Fetches the latest `limit` messages of each of the provided `chats`
+    /// sorted from the oldest to the newest
+    pub async fn fetch_recent_messages(&self, chats: &[Chat], limit: i64) -> Result<Vec<Message>> {
+        let chats_ids: Vec<Uuid> = chats.iter().map(|chat| chat.id).collect();
+        let messages = sqlx::query_file_as!(
+            MessageDTO,
+            "sql/fetch_recent_messages.sql",
+            &chats_ids,
+            limit
+        )
+        .fetch_all(self.db_pool)
+        .await?
+        .into_iter()
+        .filter_map(|message| {
+            let chat = chats.iter().find(|chat| chat.id == message.chat_id)?;
+
+            Some(message.into_message(chat.clone()))
+        })
+        .collect();
+
+        self.with_details(messages).await
+    }
+
+    /// Fetches up to `limit` messages of the provided `chat` with a sequence
+    /// number greater than `sequence`, sorted by sequence number
+    pub async fn fetch_messages_after(
+        &self,
+        chat: &Chat,
+        sequence: i64,
+        limit: i64,
+    ) -> Result<Vec<Message>> {
+        let messages = sqlx::query_file_as!(
+            MessageDTO,
+            "sql/fetch_messages_after.sql",
+            chat.id,
+            sequence,
+            limit
+        )
+        .fetch_all(self.db_pool)
+        .await?
+        .into_iter()
+        .map(|message| message.into_message(chat.clone()))
+        .collect();
+
+        self.with_details(messages).await
+    }
+
+    /// Searches the messages of the provided `chats` matching the provided
+    /// `search`, skipping the first `offset` results and retrieving up to
+    /// `limit` results sorted from the newest message to the oldest
+    pub async fn search(
+        &self,
+        chats: &[Chat],
+        search: &MessagesSearch,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<SearchResult>> {
+        let chats_ids: Vec<Uuid> = chats.iter().map(|chat| chat.id).collect();
+        let rows = sqlx::query(
+            r#"
+            WITH query AS (
+                SELECT
+                    websearch_to_tsquery('simple', $2) AS tsquery
+            )
+            SELECT
+                messages.id AS message_id,
+                messages.content AS message_content,
+                messages.sequence AS message_sequence,
+                messages.created_at AS message_created_at,
+                messages.edited_at AS message_edited_at,
+                messages.deleted_at AS message_deleted_at,
+                messages.kind AS message_kind,
+                messages.reply_to_id AS message_reply_to_id,
+                messages.expires_at AS message_expires_at,
+                files.id AS file_id,
+                files.filename AS file_filename,
+                files.mime AS file_mime,
+                files.size AS file_size,
+                files.url AS file_url,
+                users.id AS author_id,
+                users. "name" AS author_name,
+                messages.chat_id AS chat_id,
+                ts_headline('simple', translate(messages.content, $9, ''), query.tsquery, $10) AS message_headline
+            FROM
+                messages
+                CROSS JOIN query
+                INNER JOIN users ON users.id = messages.author_id
+                LEFT JOIN files ON files.id = messages.file_id
+            WHERE
+                messages.chat_id = ANY ($1)
+                AND to_tsvector('simple', messages.content) @@ query.tsquery
+                AND messages.deleted_at IS NULL
+                AND ($3::UUID IS NULL
+                    OR messages.chat_id = $3)
+                AND ($4::UUID IS NULL
+                    OR messages.author_id = $4)
+                AND ($5::TIMESTAMPTZ IS NULL
+                    OR messages.created_at >= $5)
+                AND ($6::TIMESTAMPTZ IS NULL
+                    OR messages.created_at <= $6)
+            ORDER BY
+                messages.created_at DESC,
+                messages.id DESC
+            LIMIT $7 OFFSET $8;
+            "#,
+        )
+        .bind(&chats_ids)
+        .bind(&search.query)
+        .bind(search.chat_id)
+        .bind(search.author_id)
+        .bind(search.from)
+        .bind(search.to)
+        .bind(limit)
+        .bind(offset)
+        .bind(format!("{}{}", HEADLINE_START_SEL, HEADLINE_STOP_SEL))
+        .bind(format!(
+            "StartSel={}, StopSel={}, MaxFragments=2",
+            HEADLINE_START_SEL, HEADLINE_STOP_SEL
+        ))
+        .fetch_all(self.db_pool)
+        .await?;
+        let mut messages = Vec::with_capacity(rows.len());
+        let mut headlines: Vec<String> = Vec::with_capacity(rows.len());
+
+        for row in rows {
+            let message = MessageDTO::from_row(&row)?;
+
+            if let Some(chat) = chats.iter().find(|chat| chat.id == message.chat_id) {
+                headlines.push(row.try_get("message_headline")?);
+                messages.push(message.into_message(chat.clone()));
+            }
+        }
+
+        let results = self
+            .with_details(messages)
+            .await?
+            .into_iter()
+            .zip(headlines)
+            .map(|(message, headline)| SearchResult::new(message, &headline))
+            .collect();
+
+        Ok(results)
+    }
+
+    /// Replaces the body of a message of the provided `chat` written by the
+    /// user with the provided `author_id`
+    pub async fn update_body(
+        &self,
+        chat: &Chat,
+        message_id: &Uuid,
+        author_id: &Uuid,
+        body: &str,
+        mentions: &[Mention],
+    ) -> Result<Message> {
+        let (mentioned_ids, offsets, lengths) = unzip_mentions(mentions);
+        let message: Option<MessageDTO> = sqlx::query_as(
+            r#"
+            WITH message AS (
+                UPDATE
+                    messages
+                SET
+                    content = $4,
+                    edited_at = CURRENT_TIMESTAMP,
+                    updated_at = CURRENT_TIMESTAMP
+                WHERE
+                    messages.id = $1
+                    AND messages.chat_id = $2
+                    AND messages.author_id = $3
+                    AND messages.deleted_at IS NULL
+                RETURNING
+                    *
+            ),
+            removed_mentions AS (
+                DELETE FROM message_mentions
+                WHERE message_mentions.message_id IN (
+                        SELECT
+                            message.id
+                        FROM
+                            message)
+            ),
+            mentioned AS (
+                INSERT INTO message_mentions (message_id, user_id, "offset", length)
+                SELECT
+                    message.id,
+                    mention.user_id,
+                    mention.offset,
+                    mention.length
+                FROM
+                    message
+                    CROSS JOIN UNNEST($5::UUID[], $6::INTEGER[], $7::INTEGER[]) AS mention (user_id, "offset", length)
+            )
+            SELECT
+                message.id AS message_id,
+                message.content AS message_content,
+                message.sequence AS message_sequence,
+                message.created_at AS message_created_at,
+                message.edited_at AS message_edited_at,
+                message.deleted_at AS message_deleted_at,
+                message.kind AS message_kind,
+                message.reply_to_id AS message_reply_to_id,
+                message.expires_at AS message_expires_at,
+                files.id AS file_id,
+                files.filename AS file_filename,
+                files.mime AS file_mime,
+                files.size AS file_size,
+                files.url AS file_url,
+                users.id AS author_id,
+                users. "name" AS author_name,
+                message.chat_id AS chat_id
+            FROM
+                message
+                INNER JOIN users ON users.id = message.author_id
+                LEFT JOIN files ON files.id = message.file_id;
+            "#,
+        )
+        .bind(message_id)
+        .bind(chat.id)
+        .bind(author_id)
+        .bind(body)
+        .bind(&mentioned_ids)
+        .bind(&offsets)
+        .bind(&lengths)
+        .fetch_optional(self.db_pool)
+        .await?;
+
+        match message {
+            Some(message) => {
+                let mut messages = self
+                    .with_details(vec![message.into_message(chat.clone())])
+                    .await?;
+
+                Ok(messages.remove(0))
+            }
+            None => Err(self.find_author_error(chat, message_id, author_id).await),
+        }
+    }
+
+    /// Soft deletes a message of the provided `chat` written by the user with
+    /// the provided `author_id`, leaving a tombstone without body, attachment,
+    /// reactions nor mentions in place. Deleted messages are unpinned
+    pub async fn soft_delete(
+        &self,
+        chat: &Chat,
+        message_id: &Uuid,
+        author_id: &Uuid,
+    ) -> Result<Message> {
+        let message: Option<MessageDTO> = sqlx::query_as(
+            r#"
+            WITH message AS (
+                UPDATE
+                    messages
+                SET
+                    content = '',
+                    file_id = NULL,
+                    deleted_at = CURRENT_TIMESTAMP,
+                    updated_at = CURRENT_TIMESTAMP
+                WHERE
+                    messages.id = $1
+                    AND messages.chat_id = $2
+                    AND messages.author_id = $3
+                    AND messages.deleted_at IS NULL
+                RETURNING
+                    *
+            ),
+            reactions AS (
+                DELETE FROM message_reactions
+                WHERE message_reactions.message_id IN (
+                        SELECT
+                            message.id
+                        FROM
+                            message)
+            ),
+            mentions AS (
+                DELETE FROM message_mentions
+                WHERE message_mentions.message_id IN (
+                        SELECT
+                            message.id
+                        FROM
+                            message)
+            ),
+            pins AS (
+                DELETE FROM chat_pins
+                WHERE chat_pins.message_id IN (
+                        SELECT
+                            message.id
+                        FROM
+                            message)
+            )
+            SELECT
+                message.id AS message_id,
+                message.content AS message_content,
+                message.sequence AS message_sequence,
+                message.created_at AS message_created_at,
+                message.edited_at AS message_edited_at,
+                message.deleted_at AS message_deleted_at,
+                message.kind AS message_kind,
+                message.reply_to_id AS message_reply_to_id,
+                message.expires_at AS message_expires_at,
+                files.id AS file_id,
+                files.filename AS file_filename,
+                files.mime AS file_mime,
+                files.size AS file_size,
+                files.url AS file_url,
+                users.id AS author_id,
+                users. "name" AS author_name,
+                message.chat_id AS chat_id
+            FROM
+                message
+                INNER JOIN users ON users.id = message.author_id
+                LEFT JOIN files ON files.id = message.file_id;
+            "#,
+        )
+        .bind(message_id)
+        .bind(chat.id)
+        .bind(author_id)
+        .fetch_optional(self.db_pool)
+        .await?;
+
+        match message {
+            Some(message) => Ok(message.into_message(chat.clone())),
+            None => Err(self.find_author_error(chat, message_id, author_id).await),
+        }
+    }
+
+    /// Hard deletes up to `limit` messages which expired, along with their
+    /// reactions, mentions and pins, retrieving the IDs of the purged
+    /// messages grouped by chat.
+    ///
+    /// Attached files are deleted as well unless they are referenced by
+    /// other messages, scheduled messages or avatars, replies to purged
+    /// messages are kept without quote.
+    pub async fn purge_expired(&self, limit: i64) -> Result<Vec<MessagesExpired>> {
+        let mut tx = self.db_pool.begin().await?;
+        let rows = sqlx::query(
+            r#"
+            DELETE FROM messages
+            WHERE messages.id IN (
+                    SELECT
+                        id
+                    FROM
+                        messages
+                    WHERE
+                        expires_at <= CURRENT_TIMESTAMP
+                    ORDER BY
+                        expires_at ASC
+                    LIMIT $1
+                    FOR UPDATE SKIP LOCKED)
+            RETURNING
+                id,
+                chat_id,
+                file_id"#,
+        )
+        .bind(limit)
+        .fetch_all(&mut tx)
+        .await?;
+
+        let mut expired: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+        let mut files_ids: Vec<Uuid> = Vec::new();
+
+        for row in rows.iter() {
+            let chat_id: Uuid = row.try_get("chat_id")?;
+
+            expired.entry(chat_id).or_default().push(row.try_get("id")?);
+
+            if let Some(file_id) = row.try_get::<Option<Uuid>, _>("file_id")? {
+                files_ids.push(file_id);
+            }
+        }
+
+        if !files_ids.is_empty() {
+            sqlx::query(
+                r#"
+                DELETE FROM files
+                WHERE files.id = ANY ($1)
+                    AND NOT EXISTS (
+                        SELECT
+                            1
+                        FROM
+                            messages
+                        WHERE
+                            messages.file_id = files.id)
+                    AND NOT EXISTS (
+                        SELECT
+                            1
+                        FROM
+                            scheduled_messages
+                        WHERE
+                            scheduled_messages.file_id = files.id)
+                    AND NOT EXISTS (
+                        SELECT
+                            1
+                        FROM
+                            avatars
+                        WHERE
+                            avatars.file_id = files.id)"#,
+            )
+            .bind(&files_ids)
+            .execute(&mut tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(expired
+            .into_iter()
+            .map(|(chat_id, messages_ids)| MessagesExpired {
+                chat_id,
+                messages_ids,
+            })
+            .collect())
+    }
+
+    /// Pins a message of the provided `chat` on behalf of the user with the
+    /// provided `user_id`, retrieving `None` if the message was already
+    /// pinned
+    pub async fn pin(
+        &self,
+        chat: &Chat,
+        message_id: &Uuid,
+        user_id: &Uuid,
+    ) -> Result<Option<DateTime<Utc>>> {
+        let row = sqlx::query(
+            r#"
+            WITH message AS (
+                SELECT
+                    messages.id
+                FROM
+                    messages
+                WHERE
+                    messages.id = $1
+                    AND messages.chat_id = $2
+                    AND messages.deleted_at IS NULL
+            ),
+            pinned AS (
+                INSERT INTO chat_pins (chat_id, message_id, pinned_by)
+                SELECT
+                    $2,
+                    message.id,
+                    $3
+                FROM
+                    message
+                ON CONFLICT (chat_id, message_id) DO NOTHING
+                RETURNING
+                    pinned_at
+            )
+            SELECT
+                (SELECT pinned_at FROM pinned) AS pinned_at
+            FROM
+                message;
+            "#,
+        )
+        .bind(message_id)
+        .bind(chat.id)
+        .bind(user_id)
+        .fetch_optional(self.db_pool)
+        .await?;
+
+        match row {
+            Some(row) => Ok(row.try_get("pinned_at")?),
+            None => Err(Error::MessageNotFound(*message_id)),
+        }
+    }
+
+    /// Unpins a message of the provided `chat`, retrieving `false` if the
+    /// message was not pinned
+    pub async fn unpin(&self, chat: &Chat, message_id: &Uuid) -> Result<bool> {
+        let done = sqlx::query("DELETE FROM chat_pins WHERE chat_id = $1 AND message_id = $2")
+            .bind(chat.id)
+            .bind(message_id)
+            .execute(self.db_pool)
+            .await?;
+
+        Ok(done.rows_affected() > 0)
+    }
+
+    /// Fetches the pinned messages of the provided `chat` sorted from the
+    /// latest pinned message to the first one
+    pub async fn fetch_pins(&self, chat: &Chat) -> Result<Vec<Pin>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                messages.id AS message_id,
+                messages.content AS message_content,
+                messages.sequence AS message_sequence,
+                messages.created_at AS message_created_at,
+                messages.edited_at AS message_edited_at,
+                messages.deleted_at AS message_deleted_at,
+                messages.kind AS message_kind,
+                messages.reply_to_id AS message_reply_to_id,
+                messages.expires_at AS message_expires_at,
+                files.id AS file_id,
+                files.filename AS file_filename,
+                files.mime AS file_mime,
+                files.size AS file_size,
+                files.url AS file_url,
+                users.id AS author_id,
+                users. "name" AS author_name,
+                messages.chat_id AS chat_id,
+                chat_pins.pinned_by AS pin_pinned_by,
+                chat_pins.pinned_at AS pin_pinned_at
+            FROM
+                chat_pins
+                INNER JOIN messages ON messages.id = chat_pins.message_id
+                INNER JOIN users ON users.id = messages.author_id
+                LEFT JOIN files ON files.id = messages.file_id
+            WHERE
+                chat_pins.chat_id = $1
+            ORDER BY
+                chat_pins.pinned_at DESC;
+            "#,
+        )
+        .bind(chat.id)
+        .fetch_all(self.db_pool)
+        .await?;
+        let mut messages = Vec::with_capacity(rows.len());
+        let mut pins = Vec::with_capacity(rows.len());
+
+        for row in rows {
+            let pinned_by: Uuid = row.try_get("pin_pinned_by")?;
+            let pinned_at: DateTime<Utc> = row.try_get("pin_pinned_at")?;
+
+            messages.push(MessageDTO::from_row(&row)?.into_message(chat.clone()));
+            pins.push((pinned_by, pinned_at));
+        }
+
+        let pins = self
+            .with_details(messages)
+            .await?
+            .into_iter()
+            .zip(pins)
+            .map(|(message, (pinned_by, pinned_at))| Pin {
+                chat_id: chat.id,
+                message,
+                pinned_by,
+                pinned_at,
+            })
+            .collect();
+
+        Ok(pins)
+    }
+
+    /// Stores a message of the user with the provided `author_id` to be
+    /// delivered at `send_at`
+    pub async fn schedule(
+        &self,
+        author_id: &Uuid,
+        input_proto_message: InputProtoMessageDTO,
+        send_at: &DateTime<Utc>,
+    ) -> Result<ScheduledMessage> {
+        let scheduled: ScheduledMessageDTO = sqlx::query_as(
+            r#"
+            INSERT INTO scheduled_messages (content, kind, author_id, chat_id, file_id, reply_to_id, send_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING
+                *"#,
+        )
+        .bind(input_proto_message.body)
+        .bind(input_proto_message.kind.to_string())
+        .bind(author_id)
         .bind(input_proto_message.chat_id)
-        .fetch(self.db_pool);
-
-        while let Some(row) = rows.try_next().await? {
-            let message_id: Uuid = row.try_get("message_id")?;
-            let message_content: String = row.try_get("message_content")?;
-            let message_created_at: DateTime<Utc> = row.try_get("message_created_at")?;
-            let author_id: Uuid = row.try_get("author_id")?;
-            let author_name: String = row.try_get("author_name")?;
-
-            return Ok(Message {
-                id: message_id,
-                author: User {
-                    id: author_id,
-                    name: author_name,
-                },
-                chat,
-                body: message_content,
-                created_at: message_created_at,
-            });
+        .bind(input_proto_message.file_id)
+        .bind(input_proto_message.reply_to)
+        .bind(send_at)
+        .fetch_one(self.db_pool)
+        .await?;
+
+        Ok(scheduled.into_scheduled_message())
+    }
+
+    /// Fetches the pending scheduled messages of the user with the provided
+    /// `author_id` in the provided `chat`, sorted by delivery time
+    pub async fn fetch_scheduled(
+        &self,
+        chat: &Chat,
+        author_id: &Uuid,
+    ) -> Result<Vec<ScheduledMessage>> {
+        let scheduled: Vec<ScheduledMessageDTO> = sqlx::query_as(
+            r#"
+            SELECT
+                *
+            FROM
+                scheduled_messages
+            WHERE
+                chat_id = $1
+                AND author_id = $2
+            ORDER BY
+                send_at ASC,
+                created_at ASC"#,
+        )
+        .bind(chat.id)
+        .bind(author_id)
+        .fetch_all(self.db_pool)
+        .await?;
+
+        Ok(scheduled
+            .into_iter()
+            .map(ScheduledMessageDTO::into_scheduled_message)
+            .collect())
+    }
+
+    /// Fetches up to `limit` scheduled messages which are due, sorted by
+    /// delivery time
+    pub async fn fetch_due_scheduled(&self, limit: i64) -> Result<Vec<ScheduledMessage>> {
+        let scheduled: Vec<ScheduledMessageDTO> = sqlx::query_as(
+            r#"
+            SELECT
+                *
+            FROM
+                scheduled_messages
+            WHERE
+                send_at <= CURRENT_TIMESTAMP
+            ORDER BY
+                send_at ASC,
+                created_at ASC
+            LIMIT $1"#,
+        )
+        .bind(limit)
+        .fetch_all(self.db_pool)
+        .await?;
+
+        Ok(scheduled
+            .into_iter()
+            .map(ScheduledMessageDTO::into_scheduled_message)
+            .collect())
+    }
+
+    /// Deletes a pending scheduled message of the provided `chat` written by
+    /// the user with the provided `author_id`, retrieving the deleted
+    /// scheduled message
+    pub async fn cancel_scheduled(
+        &self,
+        chat: &Chat,
+        author_id: &Uuid,
+        scheduled_message_id: &Uuid,
+    ) -> Result<ScheduledMessage> {
+        let scheduled: Option<ScheduledMessageDTO> = sqlx::query_as(
+            r#"
+            DELETE FROM scheduled_messages
+            WHERE id = $1
+                AND chat_id = $2
+                AND author_id = $3
+            RETURNING
+                *"#,
+        )
+        .bind(scheduled_message_id)
+        .bind(chat.id)
+        .bind(author_id)
+        .fetch_optional(self.db_pool)
+        .await?;
+
+        scheduled
+            .map(ScheduledMessageDTO::into_scheduled_message)
+            .ok_or(Error::ScheduledMessageNotFound(*scheduled_message_id))
+    }
+
+    /// Deletes a scheduled message once it is delivered
+    pub async fn remove_scheduled(&self, scheduled_message_id: &Uuid) -> Result<()> {
+        sqlx::query("DELETE FROM scheduled_messages WHERE id = $1")
+            .bind(scheduled_message_id)
+            .execute(self.db_pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Adds a reaction of the user with the provided `user_id` to a message
+    /// of the provided `chat`, retrieving `false` if the user already
+    /// reacted to the message with the same emoji
+    pub async fn add_reaction(
+        &self,
+        chat: &Chat,
+        message_id: &Uuid,
+        user_id: &Uuid,
+        emoji: &str,
+    ) -> Result<bool> {
+        let row = sqlx::query(
+            r#"
+            WITH message AS (
+                SELECT
+                    messages.id
+                FROM
+                    messages
+                WHERE
+                    messages.id = $1
+                    AND messages.chat_id = $2
+                    AND messages.deleted_at IS NULL
+            ),
+            inserted AS (
+                INSERT INTO message_reactions (message_id, user_id, emoji)
+                SELECT
+                    message.id,
+                    $3,
+                    $4
+                FROM
+                    message
+                ON CONFLICT (message_id, user_id, emoji) DO NOTHING
+                RETURNING
+                    message_id
+            )
+            SELECT
+                EXISTS (SELECT 1 FROM inserted) AS added
+            FROM
+                message;
+            "#,
+        )
+        .bind(message_id)
+        .bind(chat.id)
+        .bind(user_id)
+        .bind(emoji)
+        .fetch_optional(self.db_pool)
+        .await?;
+
+        match row {
+            Some(row) => Ok(row.try_get("added")?),
+            None => Err(Error::MessageNotFound(*message_id)),
         }
+    }
 
-        Err(Error::UnableToStoreMessage)
-    }
-
-    pub async fn fetch_chat_messages(&self, chat_id: &Uuid) -> Result<Vec<Message>> {
-        let mut messages: Vec<Message> = Vec::new();
-        let mut rows =
-            sqlx::query_file!("sql/fetch_chat_messages.sql", chat_id).fetch(self.db_pool);
-
-        while let Some(row) = rows.try_next().await? {
-            messages.push(Message {
-                id: row.message_id,
-                author: User {
-                    id: row.author_id,
-                    name: row.author_name,
-                },
-                body: row.message_content,
-                chat: Chat {
-                    id: row.chat_id,
-                    participants_ids: vec![],
-                },
-                created_at: row.message_created_at,
-            });
+    /// Removes a reaction of the user with the provided `user_id` from a
+    /// message of the provided `chat`, retrieving `false` if the user didn't
+    /// react to the message with the provided emoji
+    pub async fn remove_reaction(
+        &self,
+        chat: &Chat,
+        message_id: &Uuid,
+        user_id: &Uuid,
+        emoji: &str,
+    ) -> Result<bool> {
+        let row = sqlx::query(
+            r#"
+            WITH message AS (
+                SELECT
+                    messages.id
+                FROM
+                    messages
+                WHERE
+                    messages.id = $1
+                    AND messages.chat_id = $2
+                    AND messages.deleted_at IS NULL
+            ),
+            removed AS (
+                DELETE FROM message_reactions
+                WHERE message_reactions.message_id IN (
+                        SELECT
+                            message.id
+                        FROM
+                            message)
+                    AND message_reactions.user_id = $3
+                    AND message_reactions.emoji = $4
+                RETURNING
+                    message_id
+            )
+            SELECT
+                EXISTS (SELECT 1 FROM removed) AS removed
+            FROM
+                message;
+            "#,
+        )
+        .bind(message_id)
+        .bind(chat.id)
+        .bind(user_id)
+        .bind(emoji)
+        .fetch_optional(self.db_pool)
+        .await?;
+
+        match row {
+            Some(row) => Ok(row.try_get("removed")?),
+            None => Err(Error::MessageNotFound(*message_id)),
+        }
+    }
+
+    /// Fetches the reactions of the provided messages grouped by emoji, from
+    /// the first emoji used to the last one
+    pub async fn fetch_reactions(
+        &self,
+        messages_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, Vec<ReactionCount>>> {
+        let mut reactions: HashMap<Uuid, Vec<ReactionCount>> = HashMap::new();
+        let rows = sqlx::query_file_as!(ReactionDTO, "sql/fetch_reactions.sql", messages_ids)
+            .fetch_all(self.db_pool)
+            .await?;
+
+        for row in rows {
+            reactions
+                .entry(row.message_id)
+                .or_default()
+                .push(row.into_reaction_count());
+        }
+
+        Ok(reactions)
+    }
+
+    /// Fills the reactions, the mentions and the quotes of the replied
+    /// messages of the provided messages
+    async fn with_details(&self, mut messages: Vec<Message>) -> Result<Vec<Message>> {
+        let messages_ids: Vec<Uuid> = messages.iter().map(|message| message.id).collect();
+        let replied_ids: Vec<Uuid> = messages
+            .iter()
+            .filter_map(|message| message.reply_to)
+            .collect();
+        let mut reactions = self.fetch_reactions(&messages_ids).await?;
+        let mut mentions = self.fetch_mentions(&messages_ids).await?;
+        let mut forwards = self.fetch_forwards(&messages_ids).await?;
+        let quotes = if replied_ids.is_empty() {
+            HashMap::new()
+        } else {
+            self.fetch_quotes(&replied_ids).await?
+        };
+
+        for message in messages.iter_mut() {
+            message.reactions = reactions.remove(&message.id).unwrap_or_default();
+            message.mentions = mentions.remove(&message.id).unwrap_or_default();
+            message.quote = message
+                .reply_to
+                .and_then(|reply_to| quotes.get(&reply_to).cloned());
+            message.forwarded_from = forwards.remove(&message.id);
         }
 
         Ok(messages)
     }
+
+    /// Finds a message of the provided `chat`
+    pub async fn find_message(&self, chat: &Chat, message_id: &Uuid) -> Result<Message> {
+        let message = sqlx::query_file_as!(MessageDTO, "sql/find_message.sql", message_id, chat.id)
+            .fetch_optional(self.db_pool)
+            .await?
+            .ok_or(Error::MessageNotFound(*message_id))?;
+        let mut messages = self
+            .with_details(vec![message.into_message(chat.clone())])
+            .await?;
+
+        Ok(messages.remove(0))
+    }
+
+    /// Fetches up to `limit` replies to the message with the provided
+    /// `message_id` with a sequence number greater than `after`, sorted by
+    /// sequence number
+    pub async fn fetch_thread_replies(
+        &self,
+        chat: &Chat,
+        message_id: &Uuid,
+        after: i64,
+        limit: i64,
+    ) -> Result<Vec<Message>> {
+        let messages = sqlx::query_file_as!(
+            MessageDTO,
+            "sql/fetch_thread_replies.sql",
+            chat.id,
+            message_id,
+            after,
+            limit
+        )
+        .fetch_all(self.db_pool)
+        .await?
+        .into_iter()
+        .map(|message| message.into_message(chat.clone()))
+        .collect();
+
+        self.with_details(messages).await
+    }
+
+    /// Fetches the mentions of the provided messages sorted by offset
+    pub async fn fetch_mentions(
+        &self,
+        messages_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, Vec<Mention>>> {
+        let mut mentions: HashMap<Uuid, Vec<Mention>> = HashMap::new();
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                message_id,
+                user_id,
+                "offset",
+                length
+            FROM
+                message_mentions
+            WHERE
+                message_id = ANY ($1)
+            ORDER BY
+                "offset" ASC;
+            "#,
+        )
+        .bind(messages_ids)
+        .fetch_all(self.db_pool)
+        .await?;
+
+        for row in rows {
+            mentions
+                .entry(row.try_get("message_id")?)
+                .or_default()
+                .push(Mention {
+                    user_id: row.try_get("user_id")?,
+                    offset: row.try_get("offset")?,
+                    length: row.try_get("length")?,
+                });
+        }
+
+        Ok(mentions)
+    }
+
+    /// Fetches the quotes of the messages with the provided `messages_ids`
+    pub async fn fetch_quotes(&self, messages_ids: &[Uuid]) -> Result<HashMap<Uuid, Quote>> {
+        let quotes = sqlx::query_as::<_, QuoteDTO>(
+            r#"
+            SELECT
+                messages.id AS message_id,
+                messages.content AS message_content,
+                messages.kind AS message_kind,
+                messages.deleted_at AS message_deleted_at,
+                users.id AS author_id,
+                users. "name" AS author_name
+            FROM
+                messages
+                INNER JOIN users ON users.id = messages.author_id
+            WHERE
+                messages.id = ANY ($1);
+            "#,
+        )
+        .bind(messages_ids)
+        .fetch_all(self.db_pool)
+        .await?
+        .into_iter()
+        .map(|quote| (quote.message_id, quote.into_quote()))
+        .collect();
+
+        Ok(quotes)
+    }
+
+    /// Fetches the provenance of the provided messages which were forwarded
+    pub async fn fetch_forwards(&self, messages_ids: &[Uuid]) -> Result<HashMap<Uuid, Forward>> {
+        let forwards = sqlx::query_as::<_, ForwardDTO>(
+            r#"
+            SELECT
+                messages.id AS message_id,
+                messages.forwarded_from_id,
+                messages.forwarded_chat_id,
+                users.id AS author_id,
+                users. "name" AS author_name
+            FROM
+                messages
+                INNER JOIN users ON users.id = messages.forwarded_author_id
+            WHERE
+                messages.id = ANY ($1)
+                AND messages.forwarded_chat_id IS NOT NULL;
+            "#,
+        )
+        .bind(messages_ids)
+        .fetch_all(self.db_pool)
+        .await?
+        .into_iter()
+        .map(|forward| (forward.message_id, forward.into_forward()))
+        .collect();
+
+        Ok(forwards)
+    }
+
+    /// Finds the file with the provided `file_id` uploaded by the user with
+    /// the provided `owner_id` to be attached to a message
+    pub async fn find_attachment(
+        &self,
+        file_id: &Uuid,
+        owner_id: &Uuid,
+    ) -> Result<Option<Attachment>> {
+        let attachment = sqlx::query_as::<_, AttachmentDTO>(
+            "SELECT id, filename, mime, size, url FROM files WHERE id = $1 AND user_id = $2",
+        )
+        .bind(file_id)
+        .bind(owner_id)
+        .fetch_optional(self.db_pool)
+        .await?
+        .map(AttachmentDTO::into_attachment);
+
+        Ok(attachment)
+    }
+
+    /// Retrieves the reason why a message of the provided `chat` couldn't be
+    /// modified by the user with the provided `author_id`
+    async fn find_author_error(&self, chat: &Chat, message_id: &Uuid, author_id: &Uuid) -> Error {
+        let row = sqlx::query(
+            "SELECT author_id FROM messages WHERE id = $1 AND chat_id = $2 AND deleted_at IS NULL",
+        )
+        .bind(message_id)
+        .bind(chat.id)
+        .fetch_optional(self.db_pool)
+        .await;
+
+        match row {
+            Ok(Some(_)) => Error::NotMessageAuthor(*author_id, *message_id),
+            Ok(None) => Error::MessageNotFound(*message_id),
+            Err(e) => Error::from(e),
+        }
+    }
+
+    /// Finds the message with the provided `client_message_id` stored by the
+    /// user with the provided `author_id` when storing it again didn't
+    /// retrieve it, which happens when retries of the same message race.
+    ///
+    /// The message is only retrieved if it belongs to the provided `chat`
+    async fn find_duplicate(
+        &self,
+        chat: &Chat,
+        author_id: &Uuid,
+        client_message_id: &Uuid,
+    ) -> Result<(Message, bool)> {
+        let row = sqlx::query(
+            "SELECT id, chat_id FROM messages WHERE author_id = $1 AND client_message_id = $2",
+        )
+        .bind(author_id)
+        .bind(client_message_id)
+        .fetch_optional(self.db_pool)
+        .await?;
+
+        match row {
+            Some(row) if row.try_get::<Uuid, _>("chat_id")? == chat.id => {
+                let message_id: Uuid = row.try_get("id")?;
+
+                Ok((self.find_message(chat, &message_id).await?, true))
+            }
+            Some(_) => Err(Error::DuplicateClientMessageId(*client_message_id)),
+            None => Err(Error::UnableToStoreMessage),
+        }
+    }
+}
+
+/// Splits the provided mentions into the arrays bound to the queries
+/// storing them
+fn unzip_mentions(mentions: &[Mention]) -> (Vec<Uuid>, Vec<i32>, Vec<i32>) {
+    let mut mentioned_ids = Vec::with_capacity(mentions.len());
+    let mut offsets = Vec::with_capacity(mentions.len());
+    let mut lengths = Vec::with_capacity(mentions.len());
+
+    for mention in mentions {
+        mentioned_ids.push(mention.user_id);
+        offsets.push(mention.offset);
+        lengths.push(mention.length);
+    }
+
+    (mentioned_ids, offsets, lengths)
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::future::join_all;
+    use std::time::Duration;
+    use tokio::time::delay_for;
+
+    use crate::domain::chat::dto::ChatDetailsDTO;
+    use crate::domain::chat::entity::MessageKind;
+    use crate::domain::chat::ChatRepository;
+    use crate::infrastructure::database::get_db_pool;
+
+    use super::*;
+
+    async fn create_user(db_pool: &DbPool) -> Uuid {
+        sqlx::query("INSERT INTO users (name) VALUES ($1) RETURNING id")
+            .bind(Uuid::new_v4().to_simple().to_string())
+            .fetch_one(db_pool)
+            .await
+            .unwrap()
+            .get("id")
+    }
+
+    fn message_dto(chat: &Chat, client_message_id: Uuid) -> InputProtoMessageDTO {
+        InputProtoMessageDTO {
+            author_id: None,
+            chat_id: chat.id,
+            body: String::from("hello"),
+            client_message_id: Some(client_message_id),
+            kind: MessageKind::default(),
+            file_id: None,
+            reply_to: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn it_acknowledges_concurrent_retries_of_the_same_message() {
+        let db_pool = get_db_pool().await;
+        let chat_repository = ChatRepository::new(db_pool);
+        let messages_repository = MessagesRepository::new(db_pool);
+        let author_id = create_user(db_pool).await;
+        let participant_id = create_user(db_pool).await;
+        let details = ChatDetailsDTO::default();
+        let chat = chat_repository
+            .create(&author_id, vec![author_id, participant_id], &details)
+            .await
+            .unwrap();
+        let other_chat = chat_repository
+            .create(&author_id, vec![author_id, participant_id], &details)
+            .await
+            .unwrap();
+        let client_message_id = Uuid::new_v4();
+        // holds the chat's sequence so every retry starts before any of
+        // them stores the message
+        let mut lock = db_pool.begin().await.unwrap();
+
+        sqlx::query("SELECT 1 FROM chats WHERE id = $1 FOR UPDATE")
+            .bind(chat.id)
+            .execute(&mut lock)
+            .await
+            .unwrap();
+
+        let retries: Vec<_> = (0..3)
+            .map(|_| {
+                let chat = chat.clone();
+
+                tokio::spawn(async move {
+                    MessagesRepository::new(db_pool)
+                        .create(
+                            chat.clone(),
+                            &author_id,
+                            message_dto(&chat, client_message_id),
+                            &[],
+                            None,
+                            None,
+                        )
+                        .await
+                })
+            })
+            .collect();
+
+        delay_for(Duration::from_millis(200)).await;
+        lock.commit().await.unwrap();
+
+        let stored: Vec<(Message, bool)> = join_all(retries)
+            .await
+            .into_iter()
+            .map(|result| result.unwrap().unwrap())
+            .collect();
+
+        assert_eq!(
+            stored.iter().filter(|(_, duplicated)| !duplicated).count(),
+            1
+        );
+        assert!(stored
+            .iter()
+            .all(|(message, _)| message.id == stored[0].0.id));
+
+        let reused = messages_repository
+            .create(
+                other_chat.clone(),
+                &author_id,
+                message_dto(&other_chat, client_message_id),
+                &[],
+                None,
+                None,
+            )
+            .await;
+
+        assert!(matches!(
+            reused,
+            Err(Error::DuplicateClientMessageId(id)) if id == client_message_id
+        ));
+    }
 }
@@ -1,90 +1,862 @@
+use chrono::{DateTime, Utc};
 use std::collections::HashMap;
+use std::sync::Arc;
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
-use crate::domain::chat::dto::InputProtoMessageDTO;
-use crate::domain::chat::entity::{Chat, Message, Output};
+use crate::application::service::UserService;
+use crate::domain::chat::dto::{ChatDetailsDTO, ChatSettingsDTO, InputProtoMessageDTO};
+use crate::domain::chat::entity::{
+    parse_mentions, sort_chat_summaries, validate_message_ttl, validate_reaction, validate_send_at,
+    Chat, ChatRole, ChatSettings, ChatSummary, Forward, Mention, Message, MessageKind,
+    MessagesCursor, MessagesExpired, MessagesPage, MessagesSearch, Pin, Reaction, ReadReceipt,
+    ScheduledMessage, SearchPage, Thread, Unpin, MAX_MENTIONS,
+};
 use crate::domain::chat::{ChatRepository, MessagesRepository};
 use crate::error::{Error, Result};
 
+/// Amount of messages per chat delivered to a client when joining the `Hub`
+const RECENT_MESSAGES_LIMIT: i64 = 20;
+
+/// Max amount of messages fetched in a single page of a chat's history
+const MAX_MESSAGES_PAGE_LIMIT: i64 = 100;
+
+/// Max amount of messages per chat replayed to a client when resuming
+const REPLAY_MESSAGES_LIMIT: i64 = 200;
+
+/// Max amount of expired messages purged at once
+const PURGE_MESSAGES_LIMIT: i64 = 500;
+
+/// Max amount of scheduled messages delivered at once
+const DELIVER_SCHEDULED_LIMIT: i64 = 100;
+
 pub struct ChatProvider {
     chats: RwLock<HashMap<Uuid, Chat>>,
     chat_repository: ChatRepository,
     messages_repository: MessagesRepository,
+    user_service: Arc<UserService>,
 }
 
 impl ChatProvider {
-    pub fn new(chat_repository: ChatRepository, messages_repository: MessagesRepository) -> Self {
+    pub fn new(
+        chat_repository: ChatRepository,
+        messages_repository: MessagesRepository,
+        user_service: Arc<UserService>,
+    ) -> Self {
         Self {
             chats: RwLock::new(HashMap::new()),
             chat_repository,
             messages_repository,
+            user_service,
         }
     }
 
-    pub async fn create_chat(&self, participants_ids: Vec<Uuid>) -> Result<Chat> {
+    /// Creates a chat for the provided participants, the user creating the
+    /// chat must be one of them and becomes the owner of the chat.
+    ///
+    /// Repeated participants are only added once
+    pub async fn create_chat(
+        &self,
+        creator_id: &Uuid,
+        mut participants_ids: Vec<Uuid>,
+        details: ChatDetailsDTO,
+    ) -> Result<Chat> {
+        participants_ids.sort();
+        participants_ids.dedup();
+
+        if !participants_ids.contains(creator_id) {
+            return Err(Error::ChatCreatorNotParticipant(*creator_id));
+        }
+
         if participants_ids.len() < 2 {
             return Err(Error::ChatNotEnoughParticipants(
                 participants_ids.len() as u8
             ));
         }
 
-        let chat = self.chat_repository.create(participants_ids).await?;
+        let chat = self
+            .chat_repository
+            .create(creator_id, participants_ids, &details)
+            .await?;
 
         self.chats.write().await.insert(chat.id, chat.clone());
 
         Ok(chat)
     }
 
+    /// Finds the direct chat between the provided users or creates it if the
+    /// users doesn't have a direct chat yet.
+    ///
+    /// The returned flag is `true` if the chat was created
+    pub async fn find_or_create_direct_chat(
+        &self,
+        user_id: &Uuid,
+        other_user_id: &Uuid,
+    ) -> Result<(Chat, bool)> {
+        if user_id == other_user_id {
+            return Err(Error::DirectChatWithSelf);
+        }
+
+        if let Some(chat) = self
+            .chat_repository
+            .find_direct(user_id, other_user_id)
+            .await?
+        {
+            return Ok((chat, false));
+        }
+
+        match self
+            .chat_repository
+            .create_direct(user_id, other_user_id)
+            .await?
+        {
+            Some(chat) => {
+                self.chats.write().await.insert(chat.id, chat.clone());
+
+                Ok((chat, true))
+            }
+            // the chat was created concurrently by the other user
+            None => match self
+                .chat_repository
+                .find_direct(user_id, other_user_id)
+                .await?
+            {
+                Some(chat) => Ok((chat, false)),
+                None => Err(Error::ChatNotFound),
+            },
+        }
+    }
+
     pub async fn fetch_chats(&self, user_id: &Uuid) -> Result<Vec<Chat>> {
         self.chat_repository.fetch_user_chats(user_id).await
     }
 
+    /// Fetches the chats of the user with the provided `user_id` along with
+    /// the user's unread messages count, the last message and the user's
+    /// settings of each chat.
+    ///
+    /// Either the `archived` chats of the user or the rest of them are
+    /// fetched, sorted as described by `sort_chat_summaries`
+    pub async fn fetch_chat_summaries(
+        &self,
+        user_id: &Uuid,
+        archived: bool,
+    ) -> Result<Vec<ChatSummary>> {
+        let mut settings: HashMap<Uuid, (ChatSettings, DateTime<Utc>)> = self
+            .chat_repository
+            .fetch_settings(user_id)
+            .await?
+            .into_iter()
+            .filter(|(settings, _)| settings.archived == archived)
+            .map(|(settings, joined_at)| (settings.chat_id, (settings, joined_at)))
+            .collect();
+        let chats: Vec<Chat> = self
+            .chat_repository
+            .fetch_user_chats(user_id)
+            .await?
+            .into_iter()
+            .filter(|chat| settings.contains_key(&chat.id))
+            .collect();
+        let read_states = self.chat_repository.fetch_read_states(user_id).await?;
+        let mut last_messages = self
+            .messages_repository
+            .fetch_recent_messages(&chats, 1)
+            .await?;
+
+        let mut summaries: Vec<ChatSummary> = chats
+            .into_iter()
+            .filter_map(|chat| {
+                let (settings, joined_at) = settings.remove(&chat.id)?;
+                let read_state = read_states
+                    .iter()
+                    .find(|read_state| read_state.chat_id == chat.id);
+                let last_message = last_messages
+                    .iter()
+                    .position(|message| message.chat.id == chat.id)
+                    .map(|idx| last_messages.swap_remove(idx));
+                let last_activity_at = last_message
+                    .as_ref()
+                    .map_or(joined_at, |message| message.created_at);
+
+                Some(ChatSummary {
+                    last_read_sequence: read_state.map_or(0, |rs| rs.last_read_sequence),
+                    unread_count: read_state.map_or(0, |rs| rs.unread_count),
+                    last_message,
+                    settings,
+                    last_activity_at,
+                    chat,
+                })
+            })
+            .collect();
+
+        sort_chat_summaries(&mut summaries);
+
+        Ok(summaries)
+    }
+
+    /// Updates the settings of the user with the provided `user_id` for one
+    /// of the user's chats
+    pub async fn update_chat_settings(
+        &self,
+        user_id: &Uuid,
+        chat_id: &Uuid,
+        settings: &ChatSettingsDTO,
+    ) -> Result<ChatSettings> {
+        let chat = self.find_participant_chat(user_id, chat_id).await?;
+
+        self.chat_repository
+            .update_settings(&chat.id, user_id, settings)
+            .await
+    }
+
+    /// Marks the messages of a chat as read by the user with the provided
+    /// `user_id` up to the message with the provided `sequence` number.
+    ///
+    /// The returned flag is `true` if the user's last read message changed
+    pub async fn mark_read(
+        &self,
+        user_id: &Uuid,
+        chat_id: &Uuid,
+        sequence: i64,
+    ) -> Result<(ReadReceipt, bool)> {
+        let chat = self.find_participant_chat(user_id, chat_id).await?;
+
+        self.chat_repository
+            .mark_read(&chat.id, user_id, sequence)
+            .await
+    }
+
+    /// Updates the details of a chat on behalf of one of its admins
+    pub async fn update_chat_details(
+        &self,
+        user_id: &Uuid,
+        chat_id: &Uuid,
+        details: ChatDetailsDTO,
+    ) -> Result<Chat> {
+        let chat = self.find_participant_chat(user_id, chat_id).await?;
+
+        chat.ensure_group()?;
+        chat.authorize(user_id, ChatRole::Admin, "update the chat")?;
+
+        self.chat_repository
+            .update_details(&chat.id, &details)
+            .await?;
+
+        self.refresh_chat(&chat.id).await
+    }
+
+    /// Replaces the avatar of a chat on behalf of one of its admins
+    pub async fn update_chat_avatar(
+        &self,
+        user_id: &Uuid,
+        chat_id: &Uuid,
+        avatar_id: &Uuid,
+    ) -> Result<Chat> {
+        let chat = self.find_participant_chat(user_id, chat_id).await?;
+
+        chat.ensure_group()?;
+        chat.authorize(user_id, ChatRole::Admin, "update the chat")?;
+
+        self.chat_repository
+            .update_avatar(&chat.id, avatar_id)
+            .await?;
+
+        self.refresh_chat(&chat.id).await
+    }
+
+    /// Sets the `message_ttl` of a chat on behalf of one of its moderators,
+    /// messages stored from now on are purged once the TTL elapses. A
+    /// missing TTL keeps new messages forever
+    pub async fn update_message_ttl(
+        &self,
+        user_id: &Uuid,
+        chat_id: &Uuid,
+        message_ttl: Option<i32>,
+    ) -> Result<Chat> {
+        let chat = self.find_participant_chat(user_id, chat_id).await?;
+
+        authorize_moderation(&chat, user_id, "update the message TTL")?;
+        validate_message_ttl(message_ttl)?;
+
+        self.chat_repository
+            .update_message_ttl(&chat.id, message_ttl)
+            .await?;
+
+        self.refresh_chat(&chat.id).await
+    }
+
+    /// Adds users to a chat on behalf of one of its participants.
+    ///
+    /// Retrieves the updated chat along with the IDs of the users which
+    /// joined the chat, users which are already participants of the chat
+    /// or doesn't exist are skipped
+    pub async fn add_participants(
+        &self,
+        user_id: &Uuid,
+        chat_id: &Uuid,
+        participants_ids: &[Uuid],
+    ) -> Result<(Chat, Vec<Uuid>)> {
+        let chat = self.find_participant_chat(user_id, chat_id).await?;
+
+        chat.ensure_group()?;
+
+        let added_ids = self
+            .chat_repository
+            .add_participants(&chat.id, participants_ids)
+            .await?;
+        let chat = self.refresh_chat(&chat.id).await?;
+
+        Ok((chat, added_ids))
+    }
+
+    /// Removes a participant from a chat on behalf of an admin with a
+    /// higher role than the participant's one, users leave a chat by
+    /// removing themselves.
+    ///
+    /// Retrieves the updated chat along with the ID of the new owner of the
+    /// chat if the owner was removed. The chat is kept once its last
+    /// participant leaves, but it can't be found anymore
+    pub async fn remove_participant(
+        &self,
+        user_id: &Uuid,
+        chat_id: &Uuid,
+        participant_id: &Uuid,
+    ) -> Result<(Chat, Option<Uuid>)> {
+        let mut chat = self.find_participant_chat(user_id, chat_id).await?;
+
+        chat.ensure_group()?;
+
+        if participant_id != user_id {
+            chat.authorize(user_id, ChatRole::Admin, "remove participants")?;
+            chat.authorize_over(user_id, participant_id, "remove participants")?;
+        }
+
+        let previous_owner_id = chat.owner_id();
+
+        if !self
+            .chat_repository
+            .remove_participant(&chat.id, participant_id)
+            .await?
+        {
+            return Err(Error::ParticipantNotFound(*participant_id, chat.id));
+        }
+
+        match self.refresh_chat(&chat.id).await {
+            Ok(chat) => {
+                let owner_id = chat
+                    .owner_id()
+                    .filter(|owner_id| Some(*owner_id) != previous_owner_id);
+
+                Ok((chat, owner_id))
+            }
+            Err(Error::ChatNotFound) => {
+                chat.participants_ids.clear();
+                chat.roles.clear();
+
+                Ok((chat, None))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Updates the role of a participant on behalf of an admin with a
+    /// higher role than the participant's one.
+    ///
+    /// Admins are able to grant up to their own role, the owner transfers
+    /// the ownership of the chat by granting the `ChatRole::Owner` role to
+    /// another participant, becoming an admin
+    pub async fn update_participant_role(
+        &self,
+        user_id: &Uuid,
+        chat_id: &Uuid,
+        participant_id: &Uuid,
+        role: ChatRole,
+    ) -> Result<Chat> {
+        let chat = self.find_participant_chat(user_id, chat_id).await?;
+
+        chat.ensure_group()?;
+
+        if chat.role_of(participant_id).is_none() {
+            return Err(Error::ParticipantNotFound(*participant_id, chat.id));
+        }
+
+        chat.authorize(user_id, ChatRole::Admin, "update roles")?;
+        chat.authorize(user_id, role, "grant this role")?;
+        chat.authorize_over(user_id, participant_id, "update roles")?;
+
+        self.chat_repository
+            .update_role(&chat.id, participant_id, role)
+            .await?;
+
+        self.refresh_chat(&chat.id).await
+    }
+
+    /// Replaces the cached copy of a chat with the one stored, the chat is
+    /// removed from the cache if it can't be found
+    async fn refresh_chat(&self, chat_id: &Uuid) -> Result<Chat> {
+        match self.chat_repository.find_by_id(chat_id).await {
+            Ok(chat) => {
+                self.chats.write().await.insert(chat.id, chat.clone());
+
+                Ok(chat)
+            }
+            Err(e) => {
+                self.chats.write().await.remove(chat_id);
+
+                Err(e)
+            }
+        }
+    }
+
+    /// Validates and stores a message sent by the user with the provided
+    /// `author_id`, the author is expected to be the user authenticated on
+    /// the connection the message was read from.
+    ///
+    /// The returned flag is `true` if the message was already stored
     pub async fn handle_incoming_message(
         &self,
+        author_id: &Uuid,
         incoming_message: InputProtoMessageDTO,
+    ) -> Result<(Message, bool)> {
+        let chat = self
+            .validate_incoming_message(author_id, &incoming_message)
+            .await?;
+        let mentions = self.resolve_mentions(&chat, &incoming_message.body).await?;
+
+        self.messages_repository
+            .create(chat, author_id, incoming_message, &mentions, None, None)
+            .await
+    }
+
+    /// Copies a message of a chat into the chat with the provided
+    /// `target_chat_id` on behalf of the user with the provided `user_id`,
+    /// the user is expected to be a participant of both chats.
+    ///
+    /// The copy keeps the body and the attachment of the original message
+    /// and records its author and chat as provenance, replies and mentions
+    /// are not copied. The copy expires no later than the original message
+    pub async fn forward_message(
+        &self,
+        user_id: &Uuid,
+        chat_id: &Uuid,
+        message_id: &Uuid,
+        target_chat_id: &Uuid,
     ) -> Result<Message> {
-        let (chat, incoming_message) = self.validate_incoming_message(incoming_message).await?;
+        let chat = self.find_participant_chat(user_id, chat_id).await?;
+        let target_chat = self.find_participant_chat(user_id, target_chat_id).await?;
+        let message = self
+            .messages_repository
+            .find_message(&chat, message_id)
+            .await?;
+
+        if message.deleted_at.is_some() {
+            return Err(Error::MessageNotFound(*message_id));
+        }
+
+        let expires_at = message.expires_at;
+        let forwarded_from = message.forwarded_from.unwrap_or(Forward {
+            message_id: Some(message.id),
+            chat_id: chat.id,
+            author: message.author,
+        });
+        let forwarded_message = InputProtoMessageDTO {
+            author_id: None,
+            chat_id: target_chat.id,
+            body: message.body,
+            client_message_id: None,
+            kind: message.kind,
+            file_id: message.attachment.map(|attachment| attachment.id),
+            reply_to: None,
+        };
+        let (message, _) = self
+            .messages_repository
+            .create(
+                target_chat,
+                user_id,
+                forwarded_message,
+                &[],
+                Some(&forwarded_from),
+                expires_at,
+            )
+            .await?;
+
+        Ok(message)
+    }
+
+    /// Validates and stores a message of the user with the provided
+    /// `author_id` to be delivered at `send_at`, the message is validated
+    /// as if it was sent right away
+    pub async fn schedule_message(
+        &self,
+        author_id: &Uuid,
+        incoming_message: InputProtoMessageDTO,
+        send_at: DateTime<Utc>,
+    ) -> Result<ScheduledMessage> {
+        validate_send_at(&send_at, Utc::now())?;
+
+        self.validate_incoming_message(author_id, &incoming_message)
+            .await?;
+        self.messages_repository
+            .schedule(author_id, incoming_message, &send_at)
+            .await
+    }
+
+    /// Fetches the pending scheduled messages of the user with the provided
+    /// `author_id` in a chat
+    pub async fn fetch_scheduled_messages(
+        &self,
+        author_id: &Uuid,
+        chat: &Chat,
+    ) -> Result<Vec<ScheduledMessage>> {
+        self.messages_repository
+            .fetch_scheduled(chat, author_id)
+            .await
+    }
+
+    /// Cancels a pending scheduled message of the user with the provided
+    /// `author_id`
+    pub async fn cancel_scheduled_message(
+        &self,
+        author_id: &Uuid,
+        chat: &Chat,
+        scheduled_message_id: &Uuid,
+    ) -> Result<ScheduledMessage> {
+        self.messages_repository
+            .cancel_scheduled(chat, author_id, scheduled_message_id)
+            .await
+    }
 
+    /// Fetches a batch of the scheduled messages which are due
+    pub async fn fetch_due_scheduled_messages(&self) -> Result<Vec<ScheduledMessage>> {
         self.messages_repository
-            .create(chat, incoming_message)
+            .fetch_due_scheduled(DELIVER_SCHEDULED_LIMIT)
             .await
     }
 
+    /// Delivers a scheduled message which is due as a message sent by its
+    /// author, the returned flag is `true` if the message was already
+    /// delivered.
+    ///
+    /// Scheduled messages which are no longer valid, such as messages of
+    /// authors who left the chat, are dropped. Messages are kept pending
+    /// on storage failures to be delivered later
+    pub async fn deliver_scheduled_message(
+        &self,
+        scheduled_message: ScheduledMessage,
+    ) -> Result<(Message, bool)> {
+        let scheduled_message_id = scheduled_message.id;
+        let author_id = scheduled_message.author_id;
+        let result = self
+            .handle_incoming_message(&author_id, scheduled_message.into_input())
+            .await;
+
+        if !matches!(result, Err(Error::DatabaseError(_, _))) {
+            self.messages_repository
+                .remove_scheduled(&scheduled_message_id)
+                .await?;
+        }
+
+        result
+    }
+
+    /// Resolves the `@username` tokens of the provided `body` to the
+    /// participants of the provided `chat`, tokens of users who are not
+    /// participants of the chat are ignored
+    async fn resolve_mentions(&self, chat: &Chat, body: &str) -> Result<Vec<Mention>> {
+        let mut mentions = Vec::new();
+        let mut users_ids: HashMap<&str, Option<Uuid>> = HashMap::new();
+
+        for token in parse_mentions(body) {
+            if !users_ids.contains_key(token.username) {
+                if users_ids.len() == MAX_MENTIONS {
+                    continue;
+                }
+
+                let user_id = match self.user_service.find_by_name(token.username).await {
+                    Ok(user) => Some(user.id).filter(|id| chat.participants_ids.contains(id)),
+                    Err(Error::UserNotFound) => None,
+                    Err(e) => return Err(e),
+                };
+
+                users_ids.insert(token.username, user_id);
+            }
+
+            if let Some(Some(user_id)) = users_ids.get(token.username) {
+                mentions.push(Mention {
+                    user_id: *user_id,
+                    offset: token.offset,
+                    length: token.length,
+                });
+            }
+        }
+
+        Ok(mentions)
+    }
+
     async fn validate_incoming_message(
         &self,
-        incoming_message: InputProtoMessageDTO,
-    ) -> Result<(Chat, InputProtoMessageDTO)> {
-        if let Some(chat) = self.chats.read().await.get(&incoming_message.chat_id) {
-            if chat
-                .participants_ids
-                .iter()
-                .any(|participant_id| *participant_id == incoming_message.author_id)
+        author_id: &Uuid,
+        incoming_message: &InputProtoMessageDTO,
+    ) -> Result<Chat> {
+        if let Some(claimed_author_id) = incoming_message.author_id {
+            if claimed_author_id != *author_id {
+                return Err(Error::AuthorMismatch(claimed_author_id, *author_id));
+            }
+        }
+
+        let chat = self
+            .find_participant_chat(author_id, &incoming_message.chat_id)
+            .await?;
+
+        if let Some(reply_to) = incoming_message.reply_to {
+            match self
+                .messages_repository
+                .find_message(&chat, &reply_to)
+                .await
             {
-                return Ok((chat.to_owned(), incoming_message));
+                Ok(message) if message.deleted_at.is_none() => {}
+                Ok(_) | Err(Error::MessageNotFound(_)) => {
+                    return Err(Error::InvalidReplyTo(reply_to))
+                }
+                Err(e) => return Err(e),
             }
         }
 
-        if let Ok(chat) = self
-            .chat_repository
-            .find_by_id(&incoming_message.chat_id)
+        match (incoming_message.kind, incoming_message.file_id) {
+            (MessageKind::Text, None) => {}
+            (MessageKind::Text, Some(_)) => return Err(Error::UnexpectedAttachment),
+            (kind, None) => return Err(Error::MissingAttachment(kind.to_string())),
+            (kind, Some(file_id)) => {
+                let attachment = self
+                    .messages_repository
+                    .find_attachment(&file_id, author_id)
+                    .await?
+                    .ok_or(Error::AttachmentNotFound(file_id))?;
+
+                if kind == MessageKind::Image && !attachment.is_image() {
+                    return Err(Error::AttachmentNotImage(file_id));
+                }
+            }
+        }
+
+        Ok(chat)
+    }
+
+    /// Replaces the body of a message written by the user with the provided
+    /// `author_id`, the mentions of the message are replaced by the ones
+    /// found in the new body
+    pub async fn edit_message(
+        &self,
+        author_id: &Uuid,
+        chat_id: &Uuid,
+        message_id: &Uuid,
+        body: &str,
+    ) -> Result<Message> {
+        let chat = self.find_participant_chat(author_id, chat_id).await?;
+        let mentions = self.resolve_mentions(&chat, body).await?;
+
+        self.messages_repository
+            .update_body(&chat, message_id, author_id, body, &mentions)
+            .await
+    }
+
+    /// Deletes a message written by the user with the provided `author_id`
+    pub async fn delete_message(
+        &self,
+        author_id: &Uuid,
+        chat_id: &Uuid,
+        message_id: &Uuid,
+    ) -> Result<Message> {
+        let chat = self.find_participant_chat(author_id, chat_id).await?;
+
+        self.messages_repository
+            .soft_delete(&chat, message_id, author_id)
             .await
+    }
+
+    /// Pins a message of a chat on behalf of the user with the provided
+    /// `user_id`. Any participant of a direct chat is able to pin messages,
+    /// group chats require the participant to be an admin.
+    ///
+    /// The returned flag is `true` if the message was not pinned before
+    pub async fn pin_message(
+        &self,
+        user_id: &Uuid,
+        chat_id: &Uuid,
+        message_id: &Uuid,
+    ) -> Result<(Pin, bool)> {
+        let chat = self.find_participant_chat(user_id, chat_id).await?;
+
+        authorize_moderation(&chat, user_id, "pin messages")?;
+
+        match self
+            .messages_repository
+            .pin(&chat, message_id, user_id)
+            .await?
         {
+            Some(pinned_at) => {
+                let message = self
+                    .messages_repository
+                    .find_message(&chat, message_id)
+                    .await?;
+
+                Ok((
+                    Pin {
+                        chat_id: chat.id,
+                        message,
+                        pinned_by: *user_id,
+                        pinned_at,
+                    },
+                    true,
+                ))
+            }
+            None => self
+                .messages_repository
+                .fetch_pins(&chat)
+                .await?
+                .into_iter()
+                .find(|pin| pin.message.id == *message_id)
+                .map(|pin| (pin, false))
+                .ok_or(Error::MessageNotFound(*message_id)),
+        }
+    }
+
+    /// Unpins a message of a chat on behalf of the user with the provided
+    /// `user_id`, the same permissions to pin messages are required.
+    ///
+    /// The returned flag is `true` if the message was pinned
+    pub async fn unpin_message(
+        &self,
+        user_id: &Uuid,
+        chat_id: &Uuid,
+        message_id: &Uuid,
+    ) -> Result<(Unpin, bool)> {
+        let chat = self.find_participant_chat(user_id, chat_id).await?;
+
+        authorize_moderation(&chat, user_id, "pin messages")?;
+
+        let unpinned = self.messages_repository.unpin(&chat, message_id).await?;
+
+        Ok((
+            Unpin {
+                chat_id: chat.id,
+                message_id: *message_id,
+                unpinned_by: *user_id,
+            },
+            unpinned,
+        ))
+    }
+
+    /// Fetches the pinned messages of a chat
+    pub async fn fetch_pins(&self, chat: &Chat) -> Result<Vec<Pin>> {
+        self.messages_repository.fetch_pins(chat).await
+    }
+
+    /// Purges a batch of the messages which expired, retrieving the IDs of
+    /// the purged messages grouped by chat
+    pub async fn purge_expired_messages(&self) -> Result<Vec<MessagesExpired>> {
+        self.messages_repository
+            .purge_expired(PURGE_MESSAGES_LIMIT)
+            .await
+    }
+
+    /// Adds a reaction of the user with the provided `user_id` to a message.
+    ///
+    /// The returned flag is `true` if the reaction was not added before
+    pub async fn add_reaction(
+        &self,
+        user_id: &Uuid,
+        chat_id: &Uuid,
+        message_id: &Uuid,
+        emoji: &str,
+    ) -> Result<(Reaction, bool)> {
+        validate_reaction(emoji)?;
+
+        let chat = self.find_participant_chat(user_id, chat_id).await?;
+        let added = self
+            .messages_repository
+            .add_reaction(&chat, message_id, user_id, emoji)
+            .await?;
+
+        self.make_reaction(&chat, message_id, user_id, emoji, true)
+            .await
+            .map(|reaction| (reaction, added))
+    }
+
+    /// Removes a reaction of the user with the provided `user_id` from a
+    /// message.
+    ///
+    /// The returned flag is `true` if the reaction was removed
+    pub async fn remove_reaction(
+        &self,
+        user_id: &Uuid,
+        chat_id: &Uuid,
+        message_id: &Uuid,
+        emoji: &str,
+    ) -> Result<(Reaction, bool)> {
+        let chat = self.find_participant_chat(user_id, chat_id).await?;
+        let removed = self
+            .messages_repository
+            .remove_reaction(&chat, message_id, user_id, emoji)
+            .await?;
+
+        self.make_reaction(&chat, message_id, user_id, emoji, false)
+            .await
+            .map(|reaction| (reaction, removed))
+    }
+
+    async fn make_reaction(
+        &self,
+        chat: &Chat,
+        message_id: &Uuid,
+        user_id: &Uuid,
+        emoji: &str,
+        added: bool,
+    ) -> Result<Reaction> {
+        let reactions = self
+            .messages_repository
+            .fetch_reactions(&[*message_id])
+            .await?
+            .remove(message_id)
+            .unwrap_or_default();
+
+        Ok(Reaction {
+            chat_id: chat.id,
+            message_id: *message_id,
+            user_id: *user_id,
+            emoji: emoji.to_string(),
+            added,
+            reactions,
+        })
+    }
+
+    /// Finds the chat with the provided `chat_id` making sure the user with
+    /// the provided `user_id` is one of its participants.
+    ///
+    /// The cached copy of the chat is refreshed if the user is not one of
+    /// its participants
+    pub async fn find_participant_chat(&self, user_id: &Uuid, chat_id: &Uuid) -> Result<Chat> {
+        if let Some(chat) = self.chats.read().await.get(chat_id) {
             if chat
                 .participants_ids
                 .iter()
-                .any(|participant_id| *participant_id == incoming_message.author_id)
+                .any(|participant_id| participant_id == user_id)
             {
-                return Ok((chat.to_owned(), incoming_message));
+                return Ok(chat.to_owned());
             }
+        }
 
-            return Err(Error::UserDoesntBelongToChat(
-                incoming_message.author_id,
-                incoming_message.chat_id,
-            ));
+        let chat = self.refresh_chat(chat_id).await?;
+
+        if chat
+            .participants_ids
+            .iter()
+            .any(|participant_id| participant_id == user_id)
+        {
+            return Ok(chat);
         }
 
-        Err(Error::ChatNotFound)
+        Err(Error::UserDoesntBelongToChat(*user_id, *chat_id))
     }
 
     pub async fn find_chat(&self, chat_id: &Uuid) -> Result<Chat> {
@@ -94,13 +866,181 @@ impl ChatProvider {
 
         let chat = self.chat_repository.find_by_id(chat_id).await?;
 
-        match self.chats.write().await.insert(chat.id, chat) {
-            Some(chat) => Ok(chat),
-            None => Err(Error::ChatNotFound),
+        self.chats.write().await.insert(chat.id, chat.clone());
+
+        Ok(chat)
+    }
+
+    /// Fetches a page of up to `limit` messages of a chat starting from the
+    /// provided `cursor`
+    pub async fn fetch_chat_messages(
+        &self,
+        chat: &Chat,
+        cursor: MessagesCursor,
+        limit: i64,
+    ) -> Result<MessagesPage> {
+        if !(1..=MAX_MESSAGES_PAGE_LIMIT).contains(&limit) {
+            return Err(Error::InvalidMessagesLimit(limit, MAX_MESSAGES_PAGE_LIMIT));
+        }
+
+        // an extra message is fetched to know whether there are more
+        // messages after the page or not
+        match cursor {
+            MessagesCursor::Latest | MessagesCursor::Before(_) => {
+                let before = match cursor {
+                    MessagesCursor::Before(sequence) => Some(sequence),
+                    _ => None,
+                };
+                let mut messages = self
+                    .messages_repository
+                    .fetch_chat_messages(chat, before, limit + 1)
+                    .await?;
+                let has_previous = messages.len() as i64 > limit;
+
+                if has_previous {
+                    messages.remove(0);
+                }
+
+                Ok(MessagesPage {
+                    previous: messages
+                        .first()
+                        .map(|message| message.sequence)
+                        .filter(|_| has_previous),
+                    next: messages
+                        .last()
+                        .map(|message| message.sequence)
+                        .filter(|_| before.is_some()),
+                    messages,
+                })
+            }
+            MessagesCursor::After(after) => {
+                let mut messages = self
+                    .messages_repository
+                    .fetch_messages_after(chat, after, limit + 1)
+                    .await?;
+                let has_next = messages.len() as i64 > limit;
+
+                messages.truncate(limit as usize);
+
+                Ok(MessagesPage {
+                    previous: messages.first().map(|message| message.sequence),
+                    next: messages
+                        .last()
+                        .map(|message| message.sequence)
+                        .filter(|_| has_next),
+                    messages,
+                })
+            }
+        }
+    }
+
+    /// Fetches a message of a chat along with up to `limit` of its replies
+    /// with a sequence number greater than `after`
+    pub async fn fetch_thread(
+        &self,
+        chat: &Chat,
+        message_id: &Uuid,
+        after: Option<i64>,
+        limit: i64,
+    ) -> Result<Thread> {
+        if !(1..=MAX_MESSAGES_PAGE_LIMIT).contains(&limit) {
+            return Err(Error::InvalidMessagesLimit(limit, MAX_MESSAGES_PAGE_LIMIT));
+        }
+
+        let message = self
+            .messages_repository
+            .find_message(chat, message_id)
+            .await?;
+        // an extra reply is fetched to know whether there are more replies
+        // after the page or not
+        let mut replies = self
+            .messages_repository
+            .fetch_thread_replies(chat, message_id, after.unwrap_or(0), limit + 1)
+            .await?;
+        let has_next = replies.len() as i64 > limit;
+
+        replies.truncate(limit as usize);
+
+        Ok(Thread {
+            message,
+            next: replies
+                .last()
+                .map(|reply| reply.sequence)
+                .filter(|_| has_next),
+            replies,
+        })
+    }
+
+    /// Searches the messages of the chats the user with the provided
+    /// `user_id` belongs to, skipping the first `offset` results
+    pub async fn search_messages(
+        &self,
+        user_id: &Uuid,
+        search: &MessagesSearch,
+        limit: i64,
+        offset: i64,
+    ) -> Result<SearchPage> {
+        if !(1..=MAX_MESSAGES_PAGE_LIMIT).contains(&limit) {
+            return Err(Error::InvalidMessagesLimit(limit, MAX_MESSAGES_PAGE_LIMIT));
+        }
+
+        if offset < 0 {
+            return Err(Error::InvalidSearchQuery(String::from(
+                "\"offset\" must be a positive number",
+            )));
         }
+
+        search.validate()?;
+
+        let chats = self.fetch_chats(user_id).await?;
+        // an extra result is fetched to know whether there are more results
+        // after the page or not
+        let mut results = self
+            .messages_repository
+            .search(&chats, search, limit + 1, offset)
+            .await?;
+        let has_next = results.len() as i64 > limit;
+
+        results.truncate(limit as usize);
+
+        Ok(SearchPage {
+            next: Some(offset + limit).filter(|_| has_next),
+            results,
+        })
+    }
+
+    /// Fetches the most recent messages of each of the provided `chats`
+    pub async fn fetch_recent_messages(&self, chats: &[Chat]) -> Result<Vec<Message>> {
+        self.messages_repository
+            .fetch_recent_messages(chats, RECENT_MESSAGES_LIMIT)
+            .await
     }
 
-    pub async fn fetch_chat_messages(&self, chat_id: &Uuid) -> Result<Vec<Message>> {
-        self.messages_repository.fetch_chat_messages(chat_id).await
+    /// Fetches the messages of a chat the user with the provided `user_id`
+    /// missed since the message with the `last_sequence` sequence number
+    pub async fn fetch_missed_messages(
+        &self,
+        user_id: &Uuid,
+        chat_id: &Uuid,
+        last_sequence: i64,
+    ) -> Result<Vec<Message>> {
+        let chat = self.find_participant_chat(user_id, chat_id).await?;
+
+        self.messages_repository
+            .fetch_messages_after(&chat, last_sequence, REPLAY_MESSAGES_LIMIT)
+            .await
     }
 }
+
+/// Makes sure the participant with the provided `user_id` is allowed to
+/// perform the moderation `action` described on the provided `chat`, every
+/// participant moderates direct chats while group chats require admins
+fn authorize_moderation(chat: &Chat, user_id: &Uuid, action: &str) -> Result<()> {
+    let required = if chat.is_direct() {
+        ChatRole::Member
+    } else {
+        ChatRole::Admin
+    };
+
+    chat.authorize(user_id, required, action)
+}
@@ -1,26 +1,44 @@
-use futures::{StreamExt, TryStreamExt};
-use std::default::Default;
+use futures::{SinkExt, StreamExt};
 use std::sync::Arc;
-use std::time::Duration;
-use tokio::sync::broadcast::{channel, Receiver, Sender};
-use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 use tokio::time::delay_for;
 use uuid::Uuid;
 use warp::ws::WebSocket;
 
 use crate::application::service::UserService;
-use crate::domain::chat::dto::InputProtoMessageDTO;
-use crate::domain::chat::entity::{Client, FrontEnd, Input, Message, Output, Parcel, Proto};
+use crate::domain::chat::dto::{
+    ChatDetailsDTO, ChatSettingsDTO, InputProtoDeleteMessageDTO, InputProtoEditMessageDTO,
+    InputProtoMarkReadDTO, InputProtoMessageDTO, InputProtoReactionDTO, InputProtoResumeDTO,
+    InputProtoTypingDTO,
+};
+use crate::domain::chat::entity::{
+    Ack, Chat, ChatRole, ChatSettings, Client, Connection, FrontEnd, Input, InputEnvelope, Joined,
+    Message, Output, Parcel, ParticipantRemoved, ParticipantsAdded, Pin, Presence, Proto, Reaction,
+    ReadReceipt, RoleChanged, Typing, Unpin, UserJoined, UserLeft,
+};
 use crate::domain::chat::{ChatRepository, MessagesRepository};
-use crate::error::Result;
+use crate::domain::user::User;
+use crate::error::{Error, Result};
 
 use super::chat::ChatProvider;
+use super::registry::ConnectionRegistry;
+use super::typing::TypingTracker;
+
+/// Time between purges of expired messages
+const PURGE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Time between deliveries of due scheduled messages
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Time a resumed connection is given to receive the replayed messages
+const REPLAY_TIMEOUT: Duration = Duration::from_secs(30);
 
 pub struct HubService {
-    pub output_tx: Sender<Proto<Output>>,
     pub chat_provider: ChatProvider,
     pub user_service: Arc<UserService>,
-    clients: Vec<Client>,
+    registry: Arc<ConnectionRegistry>,
+    typing: TypingTracker,
 }
 
 impl HubService {
@@ -29,59 +47,192 @@ impl HubService {
         messages_repository: MessagesRepository,
         user_service: Arc<UserService>,
     ) -> Self {
-        let (output_tx, _) = channel(16_usize);
-
         Self {
-            output_tx,
-            clients: Vec::new(),
-            chat_provider: ChatProvider::new(chat_repository, messages_repository),
+            registry: Arc::new(ConnectionRegistry::new()),
+            typing: TypingTracker::new(),
+            chat_provider: ChatProvider::new(
+                chat_repository,
+                messages_repository,
+                user_service.clone(),
+            ),
             user_service,
         }
     }
 
-    /// Registers a new client (User) to the `Hub` and forwards messages
-    /// from the Hub's main channel to the client's WebSocket sink.
+    /// Registers a new connection of a client (User) to the `Hub` and
+    /// forwards the `Proto<Output>` instances delivered to the connection
+    /// to the client's WebSocket sink.
+    ///
+    /// The connection receives a `Joined` parcel before any other parcel,
+    /// and the online participants of the user's chats are notified with
+    /// `UserJoined` and `UserLeft` parcels when the user's first connection
+    /// is opened and the user's last connection is closed.
+    ///
+    /// The connection is removed from the `Hub` as soon as the WebSocket
+    /// is closed or the connection lags behind its queue.
     pub async fn register_and_listen(
         &self,
         user_id: &Uuid,
         frontend: FrontEnd,
         web_socket: WebSocket,
-        input_tx: UnboundedSender<Proto<Input>>,
+        input_tx: UnboundedSender<InputEnvelope>,
     ) -> Result<()> {
-        let output_rx = self.subscribe();
         let (sink, stream) = web_socket.split();
         let user = self.user_service.find_by_id(user_id).await?;
         let client = Client::new(user.clone(), frontend);
+        let (connection, output_rx) = Connection::new(client.clone());
+        let connection_id = connection.id;
+        let chats = self.chat_provider.fetch_chats(&user.id).await?;
+        let contacts_ids = contacts_ids(&user.id, &chats);
+        let joined = self
+            .make_joined(user.clone(), &chats, &contacts_ids)
+            .await?;
 
-        let read_process = client.read_input(stream).try_for_each(|proto| async {
-            input_tx.send(proto).unwrap();
-            Ok(())
-        });
-
-        let (client_output_tx, client_output_rx) = unbounded_channel();
+        connection.send(Proto::new_output(Parcel::Joined(joined)))?;
 
-        tokio::spawn(client_output_rx.forward(sink));
+        if self.registry.register(connection).await {
+            self.publish_presence(&user.id, &contacts_ids, true).await;
+            self.registry
+                .send_to_users(
+                    &contacts_ids,
+                    Proto::new_output(Parcel::UserJoined(UserJoined::new(user))),
+                    |_| true,
+                )
+                .await;
+        }
 
-        let write_process =
+        let read_process = async {
             client
-                .write_output(output_rx.into_stream())
-                .try_for_each(|proto| async {
-                    client_output_tx.send(Ok(proto)).unwrap();
-                    Ok(())
-                });
+                .read_input(stream)
+                .for_each(|input| async {
+                    match input {
+                        Ok(proto) => input_tx
+                            .send(InputEnvelope::new(client.user_id, connection_id, proto))
+                            .unwrap(),
+                        Err(e) => self.send_error(&client.user_id, &connection_id, &e).await,
+                    }
+                })
+                .await;
+
+            Ok(())
+        };
 
-        if let Err(_) = tokio::select! {
+        let write_process = client
+            .write_output(output_rx)
+            .forward(sink.sink_map_err(|err| Error::IO(err.to_string())));
+
+        let result = tokio::select! {
             res = read_process => res,
             res = write_process => res,
-        } {}
+        };
 
-        Ok(())
+        self.registry
+            .unregister(&client.user_id, &connection_id)
+            .await;
+
+        for (chat_id, user_id) in self.typing.stop_connection(&connection_id) {
+            self.publish_typing(&chat_id, &user_id, false).await;
+        }
+
+        self.publish_user_left(&client.user_id).await;
+
+        result
+    }
+
+    /// Builds the `Joined` parcel for an user joining the `Hub`, including
+    /// the online participants of the user's chats and the recent messages
+    /// of each chat
+    async fn make_joined(
+        &self,
+        user: User,
+        chats: &[Chat],
+        contacts_ids: &[Uuid],
+    ) -> Result<Joined> {
+        let others: Vec<User> = self
+            .registry
+            .online_clients(contacts_ids)
+            .await
+            .into_iter()
+            .map(|client| User::new(client.user_id, &client.user_name))
+            .collect();
+        let messages = self.chat_provider.fetch_recent_messages(chats).await?;
+
+        Ok(Joined::new(user, others, messages))
     }
 
-    /// Subscribes a client (User) to the Hub's main `channel`
-    /// retrieving a `Receiver` of the channel
-    pub fn subscribe(&self) -> Receiver<Proto<Output>> {
-        self.output_tx.subscribe()
+    /// Notifies the participants of the user's chats that the user left the
+    /// `Hub` and went offline, as long as the user has no other connections
+    async fn publish_user_left(&self, user_id: &Uuid) {
+        if self.registry.is_online(user_id).await {
+            return;
+        }
+
+        match self.chat_provider.fetch_chats(user_id).await {
+            Ok(chats) => {
+                let contacts_ids = contacts_ids(user_id, &chats);
+
+                self.registry
+                    .send_to_users(
+                        &contacts_ids,
+                        Proto::new_output(Parcel::UserLeft(UserLeft::new(*user_id))),
+                        |_| true,
+                    )
+                    .await;
+                self.publish_presence(user_id, &contacts_ids, false).await;
+            }
+            Err(e) => warn!("Unable to notify user {} left: {}", user_id, e),
+        }
+    }
+
+    /// Updates the last time the user was seen and notifies the provided
+    /// contacts that the user went online or offline
+    async fn publish_presence(&self, user_id: &Uuid, contacts_ids: &[Uuid], online: bool) {
+        let last_seen_at = match self.user_service.update_last_seen(user_id).await {
+            Ok(last_seen_at) => Some(last_seen_at),
+            Err(e) => {
+                warn!("Unable to update last seen of user {}: {}", user_id, e);
+                None
+            }
+        };
+        let presence = Presence::new(*user_id, online, last_seen_at);
+        let parcel = if online {
+            Parcel::Online(presence)
+        } else {
+            Parcel::Offline(presence)
+        };
+
+        self.registry
+            .send_to_users(contacts_ids, Proto::new_output(parcel), |_| true)
+            .await;
+    }
+
+    /// Retrieves the presence of the provided users on behalf of the user
+    /// with the provided `user_id`.
+    ///
+    /// Only the presence of the user and of the users sharing a chat with
+    /// the user is retrieved, other users are skipped
+    pub async fn fetch_presence(
+        &self,
+        user_id: &Uuid,
+        users_ids: &[Uuid],
+    ) -> Result<Vec<Presence>> {
+        let chats = self.chat_provider.fetch_chats(user_id).await?;
+        let contacts_ids = contacts_ids(user_id, &chats);
+        let visible_ids: Vec<Uuid> = users_ids
+            .iter()
+            .filter(|id| *id == user_id || contacts_ids.contains(id))
+            .copied()
+            .collect();
+        let online_ids = self.registry.online_users().await;
+        let presences = self
+            .user_service
+            .fetch_last_seen(&visible_ids)
+            .await?
+            .into_iter()
+            .map(|(id, last_seen_at)| Presence::new(id, online_ids.contains(&id), last_seen_at))
+            .collect();
+
+        Ok(presences)
     }
 
     /// Initializes a `loop` to publish a `Proto<Output>` which wraps
@@ -92,17 +243,179 @@ impl HubService {
 
         loop {
             delay_for(five_seconds).await;
-            for client in self.clients.iter() {
-                self.publish(Proto::poll_interval());
+            self.publish(Proto::poll_interval()).await;
+        }
+    }
+
+    /// Handles `Proto<Input>` instances coming from the WebSocket stream.
+    ///
+    /// If the `Proto<Input>` is rejected, a `Parcel::Error` is sent back to
+    /// the connection the `Proto<Input>` was read from
+    pub async fn handle_input_proto(&self, envelope: InputEnvelope) {
+        let result = match envelope.proto.inner {
+            Input::Message(incoming_message_dto) => {
+                self.handle_input_message(
+                    &envelope.user_id,
+                    &envelope.connection_id,
+                    incoming_message_dto,
+                )
+                .await
+            }
+            Input::Resume(resume_dto) => {
+                self.handle_resume(&envelope.user_id, &envelope.connection_id, resume_dto)
+                    .await
+            }
+            Input::EditMessage(InputProtoEditMessageDTO {
+                chat_id,
+                message_id,
+                body,
+            }) => self
+                .edit_message(&envelope.user_id, &chat_id, &message_id, &body)
+                .await
+                .map(|_| ()),
+            Input::DeleteMessage(InputProtoDeleteMessageDTO {
+                chat_id,
+                message_id,
+            }) => self
+                .delete_message(&envelope.user_id, &chat_id, &message_id)
+                .await
+                .map(|_| ()),
+            Input::MarkRead(InputProtoMarkReadDTO { chat_id, sequence }) => self
+                .mark_read(&envelope.user_id, &chat_id, sequence)
+                .await
+                .map(|_| ()),
+            Input::TypingStarted(InputProtoTypingDTO { chat_id }) => {
+                self.handle_typing(&envelope.user_id, &envelope.connection_id, &chat_id, true)
+                    .await
+            }
+            Input::TypingStopped(InputProtoTypingDTO { chat_id }) => {
+                self.handle_typing(&envelope.user_id, &envelope.connection_id, &chat_id, false)
+                    .await
+            }
+            Input::AddReaction(InputProtoReactionDTO {
+                chat_id,
+                message_id,
+                emoji,
+            }) => self
+                .add_reaction(&envelope.user_id, &chat_id, &message_id, &emoji)
+                .await
+                .map(|_| ()),
+            Input::RemoveReaction(InputProtoReactionDTO {
+                chat_id,
+                message_id,
+                emoji,
+            }) => self
+                .remove_reaction(&envelope.user_id, &chat_id, &message_id, &emoji)
+                .await
+                .map(|_| ()),
+        };
+
+        if let Err(e) = result {
+            self.send_error(&envelope.user_id, &envelope.connection_id, &e)
+                .await;
+        }
+    }
+
+    /// Sends a `Parcel::Error` describing the provided `Error` to one of
+    /// the user's connections
+    async fn send_error(&self, user_id: &Uuid, connection_id: &Uuid, error: &Error) {
+        debug!("Rejected input from user {}: {}", user_id, error);
+
+        self.registry
+            .send_to_connection(
+                user_id,
+                connection_id,
+                Proto::new_output(Parcel::Error(error.into())),
+            )
+            .await;
+    }
+
+    /// Initializes a `loop` to stop the typing indicators which were not
+    /// refreshed by their clients
+    pub async fn expire_typing(&self) {
+        let one_second = Duration::from_secs(1);
+
+        loop {
+            delay_for(one_second).await;
+
+            for (chat_id, user_id) in self.typing.expire(Instant::now()) {
+                self.publish_typing(&chat_id, &user_id, false).await;
+            }
+        }
+    }
+
+    /// Initializes a `loop` to purge the messages which expired, the
+    /// participants of each chat are notified with the IDs of the purged
+    /// messages
+    pub async fn purge_expired_messages(&self) {
+        loop {
+            delay_for(PURGE_INTERVAL).await;
+
+            let purged = match self.chat_provider.purge_expired_messages().await {
+                Ok(purged) => purged,
+                Err(e) => {
+                    warn!("Unable to purge expired messages: {}", e);
+                    continue;
+                }
+            };
+
+            for expired in purged {
+                match self.chat_provider.find_chat(&expired.chat_id).await {
+                    Ok(chat) => {
+                        self.publish_to_participants(
+                            &chat.participants_ids,
+                            Parcel::MessagesExpired(expired),
+                        )
+                        .await
+                    }
+                    Err(e) => warn!(
+                        "Unable to notify expired messages on chat {}: {}",
+                        expired.chat_id, e
+                    ),
+                }
             }
         }
     }
 
-    /// Handles `Proto<Input>` instances coming from the WebSocket stream
-    pub async fn handle_input_proto(&self, input_proto: Proto<Input>) {
-        match input_proto.inner {
-            Input(incoming_message_dto) => {
-                self.handle_input_message(incoming_message_dto).await;
+    /// Initializes a `loop` to deliver the scheduled messages which are
+    /// due, delivered messages are published to every connection of the
+    /// chat participants, including the author's
+    pub async fn deliver_scheduled_messages(&self) {
+        loop {
+            delay_for(SCHEDULER_INTERVAL).await;
+
+            let due = match self.chat_provider.fetch_due_scheduled_messages().await {
+                Ok(due) => due,
+                Err(e) => {
+                    warn!("Unable to fetch scheduled messages: {}", e);
+                    continue;
+                }
+            };
+
+            for scheduled_message in due {
+                let scheduled_message_id = scheduled_message.id;
+
+                match self
+                    .chat_provider
+                    .deliver_scheduled_message(scheduled_message)
+                    .await
+                {
+                    Ok((message, false)) => {
+                        let participants_ids = message.chat.participants_ids.clone();
+
+                        self.publish_mentions(&message).await;
+                        self.publish_to_participants(
+                            &participants_ids,
+                            Parcel::LocalMessage(message),
+                        )
+                        .await;
+                    }
+                    Ok((_, true)) => {}
+                    Err(e) => warn!(
+                        "Unable to deliver scheduled message {}: {}",
+                        scheduled_message_id, e
+                    ),
+                }
             }
         }
     }
@@ -111,62 +424,608 @@ impl HubService {
     /// subscribers and handles `Proto<Input>` incoming instances.
     ///
     /// `Proto<Input>` instances are expected from the `input_rx`.
-    pub async fn init(&self, input_rx: UnboundedReceiver<Proto<Input>>) {
+    pub async fn init(&self, input_rx: UnboundedReceiver<InputEnvelope>) {
         let polling = self.poll();
-        let input_handling = input_rx.for_each(|input_proto| self.handle_input_proto(input_proto));
+        let typing_expiration = self.expire_typing();
+        let messages_expiration = self.purge_expired_messages();
+        let scheduled_delivery = self.deliver_scheduled_messages();
+        let input_handling = input_rx.for_each(|envelope| self.handle_input_proto(envelope));
 
         tokio::select! {
             _ = polling => {},
+            _ = typing_expiration => {},
+            _ = messages_expiration => {},
+            _ = scheduled_delivery => {},
             _ = input_handling => {},
         }
     }
 
+    /// Handles the typing indicators of the user with the provided
+    /// `user_id`, the other participants of the chat are notified when the
+    /// user starts or stops typing.
+    ///
+    /// Typing indicators are relayed without being stored and expire unless
+    /// the client sends a `typing-started` input again before
+    /// `TYPING_EXPIRY` elapses
+    pub async fn handle_typing(
+        &self,
+        user_id: &Uuid,
+        connection_id: &Uuid,
+        chat_id: &Uuid,
+        started: bool,
+    ) -> Result<()> {
+        let chat = self
+            .chat_provider
+            .find_participant_chat(user_id, chat_id)
+            .await?;
+        let changed = if started {
+            self.typing
+                .start(chat.id, *user_id, *connection_id, Instant::now())
+        } else {
+            self.typing.stop(chat.id, *user_id)
+        };
+
+        if changed {
+            self.publish_typing(&chat.id, user_id, started).await;
+        }
+
+        Ok(())
+    }
+
+    /// Notifies the participants of a chat, other than the user with the
+    /// provided `user_id`, that the user started or stopped typing
+    async fn publish_typing(&self, chat_id: &Uuid, user_id: &Uuid, started: bool) {
+        let chat = match self.chat_provider.find_chat(chat_id).await {
+            Ok(chat) => chat,
+            Err(e) => {
+                warn!("Unable to notify typing on chat {}: {}", chat_id, e);
+                return;
+            }
+        };
+        let typing = Typing::new(*chat_id, *user_id);
+        let parcel = if started {
+            Parcel::TypingStarted(typing)
+        } else {
+            Parcel::TypingStopped(typing)
+        };
+
+        self.registry
+            .send_to_users(
+                &chat.participants_ids,
+                Proto::new_output(parcel),
+                |connection| connection.user_id() != *user_id,
+            )
+            .await;
+    }
+
     /// Handles user incoming messages and publishes them to the
     /// respective chat.
     ///
-    /// If the author doesn't belongs to the chat specified, then
-    /// is the message is not published
-    pub async fn handle_input_message(&self, incoming_message: InputProtoMessageDTO) {
-        match self
+    /// The author of the message is the user authenticated on the
+    /// connection the message was read from. If the author doesn't
+    /// belongs to the chat specified, then the message is not published.
+    ///
+    /// Messages sent with a `client_message_id` are acknowledged to the
+    /// connection they were read from, and are published only the first
+    /// time they are received
+    pub async fn handle_input_message(
+        &self,
+        author_id: &Uuid,
+        connection_id: &Uuid,
+        incoming_message: InputProtoMessageDTO,
+    ) -> Result<()> {
+        let client_message_id = incoming_message.client_message_id;
+        let (message, duplicated) = self
             .chat_provider
-            .handle_incoming_message(incoming_message)
-            .await
-        {
-            Ok(message) => {
-                self.publish_to_chat(message).await;
-                return;
+            .handle_incoming_message(author_id, incoming_message)
+            .await?;
+
+        if let Some(client_message_id) = client_message_id {
+            self.registry
+                .send_to_connection(
+                    author_id,
+                    connection_id,
+                    Proto::new_output(Parcel::Ack(Ack::new(client_message_id, &message))),
+                )
+                .await;
+        }
+
+        if !duplicated {
+            if self.typing.stop(message.chat.id, *author_id) {
+                self.publish_typing(&message.chat.id, author_id, false)
+                    .await;
             }
-            Err(e) => {}
+
+            self.publish_mentions(&message).await;
+            self.publish_to_chat(message).await;
         }
+
+        Ok(())
     }
 
-    /// Publishes an `Proto<Output>` to Hub's main channel receiver.
-    /// If there's no receivers (subscribers) available then the `Proto`
-    /// is never sent through the channel
-    pub async fn publish(&self, proto: Proto<Output>) {
-        if self.output_tx.receiver_count() == 0 {
-            return;
+    /// Handles the resume handshake of a connection, replaying the messages
+    /// missed by the client for each of the chats provided.
+    ///
+    /// Output sent to the connection while the missed messages are fetched
+    /// is held back and delivered after the replayed messages.
+    ///
+    /// The replay is delivered on its own task so slow connections don't
+    /// hold back the input of other connections, connections which don't
+    /// drain the replay within `REPLAY_TIMEOUT` are dropped
+    pub async fn handle_resume(
+        &self,
+        user_id: &Uuid,
+        connection_id: &Uuid,
+        resume: InputProtoResumeDTO,
+    ) -> Result<()> {
+        let connection = match self.registry.hold(user_id, connection_id).await {
+            Some(connection) => connection,
+            None => return Ok(()),
+        };
+        let mut replay: Vec<Proto<Output>> = Vec::new();
+
+        for resume_chat in resume.chats.into_iter() {
+            match self
+                .chat_provider
+                .fetch_missed_messages(user_id, &resume_chat.chat_id, resume_chat.last_sequence)
+                .await
+            {
+                Ok(messages) => replay.extend(
+                    messages
+                        .into_iter()
+                        .map(|message| Proto::new_output(Parcel::LocalMessage(message))),
+                ),
+                Err(e) => replay.push(Proto::new_output(Parcel::Error((&e).into()))),
+            }
         }
 
-        // safe to unwrap due tot available receivers
-        // are checked before sending
-        self.output_tx.send(proto).unwrap();
-    }
+        let registry = self.registry.clone();
+        let user_id = *user_id;
+        let connection_id = *connection_id;
 
-    /// Sends an `Proto<Output>` which wraps a `Message` through the
-    /// Hub's main `channel` filtering the Author of the message
-    pub async fn publish_to_chat(&self, message: Message) {
-        let chat = message.chat.clone();
-        let message_author_id = message.author.id.clone();
-
-        if self.output_tx.receiver_count() > 0 {
-            for participant_id in chat.participants_ids.iter() {
-                if *participant_id != message_author_id {
-                    self.output_tx
-                        .send(Proto::new_output(Parcel::LocalMessage(message.clone())))
-                        .unwrap();
-                }
+        tokio::spawn(async move {
+            if let Err(e) = connection.release(replay, REPLAY_TIMEOUT).await {
+                warn!("Dropping connection of user {}: {}", user_id, e);
+                registry.unregister(&user_id, &connection_id).await;
             }
+        });
+
+        Ok(())
+    }
+
+    /// Edits a message written by the user with the provided `author_id`
+    /// and notifies every connection of the chat participants, including
+    /// the author's
+    pub async fn edit_message(
+        &self,
+        author_id: &Uuid,
+        chat_id: &Uuid,
+        message_id: &Uuid,
+        body: &str,
+    ) -> Result<Message> {
+        let message = self
+            .chat_provider
+            .edit_message(author_id, chat_id, message_id, body)
+            .await?;
+
+        self.publish_to_participants(
+            &message.chat.participants_ids,
+            Parcel::MessageEdited(message.clone()),
+        )
+        .await;
+
+        Ok(message)
+    }
+
+    /// Forwards a message to another chat of the user with the provided
+    /// `user_id`, the forwarded message is published to every connection
+    /// of the target chat participants, including the user's
+    pub async fn forward_message(
+        &self,
+        user_id: &Uuid,
+        chat_id: &Uuid,
+        message_id: &Uuid,
+        target_chat_id: &Uuid,
+    ) -> Result<Message> {
+        let message = self
+            .chat_provider
+            .forward_message(user_id, chat_id, message_id, target_chat_id)
+            .await?;
+
+        self.publish_to_participants(
+            &message.chat.participants_ids,
+            Parcel::LocalMessage(message.clone()),
+        )
+        .await;
+
+        Ok(message)
+    }
+
+    /// Deletes a message written by the user with the provided `author_id`
+    /// and notifies every connection of the chat participants, including
+    /// the author's
+    pub async fn delete_message(
+        &self,
+        author_id: &Uuid,
+        chat_id: &Uuid,
+        message_id: &Uuid,
+    ) -> Result<Message> {
+        let message = self
+            .chat_provider
+            .delete_message(author_id, chat_id, message_id)
+            .await?;
+
+        self.publish_to_participants(
+            &message.chat.participants_ids,
+            Parcel::MessageDeleted(message.clone()),
+        )
+        .await;
+
+        Ok(message)
+    }
+
+    /// Pins a message of a chat and notifies every connection of the chat
+    /// participants, including the user's, if the message was not pinned
+    /// before
+    pub async fn pin_message(
+        &self,
+        user_id: &Uuid,
+        chat_id: &Uuid,
+        message_id: &Uuid,
+    ) -> Result<Pin> {
+        let (pin, pinned) = self
+            .chat_provider
+            .pin_message(user_id, chat_id, message_id)
+            .await?;
+
+        if pinned {
+            self.publish_to_participants(
+                &pin.message.chat.participants_ids,
+                Parcel::MessagePinned(pin.clone()),
+            )
+            .await;
+        }
+
+        Ok(pin)
+    }
+
+    /// Unpins a message of a chat and notifies every connection of the chat
+    /// participants, including the user's, if the message was pinned
+    pub async fn unpin_message(
+        &self,
+        user_id: &Uuid,
+        chat_id: &Uuid,
+        message_id: &Uuid,
+    ) -> Result<Unpin> {
+        let (unpin, unpinned) = self
+            .chat_provider
+            .unpin_message(user_id, chat_id, message_id)
+            .await?;
+
+        if unpinned {
+            let chat = self.chat_provider.find_chat(chat_id).await?;
+
+            self.publish_to_participants(&chat.participants_ids, Parcel::MessageUnpinned(unpin))
+                .await;
+        }
+
+        Ok(unpin)
+    }
+
+    /// Adds a reaction of the user with the provided `user_id` to a message
+    /// and notifies every connection of the chat participants, including
+    /// the user's, if the reaction was not added before
+    pub async fn add_reaction(
+        &self,
+        user_id: &Uuid,
+        chat_id: &Uuid,
+        message_id: &Uuid,
+        emoji: &str,
+    ) -> Result<Reaction> {
+        let (reaction, added) = self
+            .chat_provider
+            .add_reaction(user_id, chat_id, message_id, emoji)
+            .await?;
+
+        if added {
+            self.publish_reaction(reaction.clone()).await?;
+        }
+
+        Ok(reaction)
+    }
+
+    /// Removes a reaction of the user with the provided `user_id` from a
+    /// message and notifies every connection of the chat participants,
+    /// including the user's, if the reaction was removed
+    pub async fn remove_reaction(
+        &self,
+        user_id: &Uuid,
+        chat_id: &Uuid,
+        message_id: &Uuid,
+        emoji: &str,
+    ) -> Result<Reaction> {
+        let (reaction, removed) = self
+            .chat_provider
+            .remove_reaction(user_id, chat_id, message_id, emoji)
+            .await?;
+
+        if removed {
+            self.publish_reaction(reaction.clone()).await?;
+        }
+
+        Ok(reaction)
+    }
+
+    async fn publish_reaction(&self, reaction: Reaction) -> Result<()> {
+        let chat = self.chat_provider.find_chat(&reaction.chat_id).await?;
+
+        self.publish_to_participants(&chat.participants_ids, Parcel::Reaction(reaction))
+            .await;
+
+        Ok(())
+    }
+
+    /// Marks the messages of a chat as read by the user with the provided
+    /// `user_id` and notifies the participants of the chat, including the
+    /// user's other connections, if the user's last read message changed
+    pub async fn mark_read(
+        &self,
+        user_id: &Uuid,
+        chat_id: &Uuid,
+        sequence: i64,
+    ) -> Result<ReadReceipt> {
+        let (receipt, updated) = self
+            .chat_provider
+            .mark_read(user_id, chat_id, sequence)
+            .await?;
+
+        if updated {
+            let chat = self.chat_provider.find_chat(chat_id).await?;
+
+            self.publish_to_participants(&chat.participants_ids, Parcel::Read(receipt))
+                .await;
+        }
+
+        Ok(receipt)
+    }
+
+    /// Updates the details of a chat and notifies its participants
+    pub async fn update_chat(
+        &self,
+        user_id: &Uuid,
+        chat_id: &Uuid,
+        details: ChatDetailsDTO,
+    ) -> Result<Chat> {
+        let chat = self
+            .chat_provider
+            .update_chat_details(user_id, chat_id, details)
+            .await?;
+
+        self.publish_to_participants(&chat.participants_ids, Parcel::ChatUpdated(chat.clone()))
+            .await;
+
+        Ok(chat)
+    }
+
+    /// Sets the message TTL of a chat and notifies its participants
+    pub async fn update_message_ttl(
+        &self,
+        user_id: &Uuid,
+        chat_id: &Uuid,
+        message_ttl: Option<i32>,
+    ) -> Result<Chat> {
+        let chat = self
+            .chat_provider
+            .update_message_ttl(user_id, chat_id, message_ttl)
+            .await?;
+
+        self.publish_to_participants(&chat.participants_ids, Parcel::ChatUpdated(chat.clone()))
+            .await;
+
+        Ok(chat)
+    }
+
+    /// Updates the settings of the user with the provided `user_id` for one
+    /// of the user's chats, the user's connections are notified in order
+    /// to keep every client of the user in sync
+    pub async fn update_chat_settings(
+        &self,
+        user_id: &Uuid,
+        chat_id: &Uuid,
+        settings: ChatSettingsDTO,
+    ) -> Result<ChatSettings> {
+        let settings = self
+            .chat_provider
+            .update_chat_settings(user_id, chat_id, &settings)
+            .await?;
+
+        self.publish_to_participants(&[*user_id], Parcel::ChatSettingsUpdated(settings))
+            .await;
+
+        Ok(settings)
+    }
+
+    /// Replaces the avatar of a chat and notifies its participants
+    pub async fn update_chat_avatar(
+        &self,
+        user_id: &Uuid,
+        chat_id: &Uuid,
+        avatar_id: &Uuid,
+    ) -> Result<Chat> {
+        let chat = self
+            .chat_provider
+            .update_chat_avatar(user_id, chat_id, avatar_id)
+            .await?;
+
+        self.publish_to_participants(&chat.participants_ids, Parcel::ChatUpdated(chat.clone()))
+            .await;
+
+        Ok(chat)
+    }
+
+    /// Adds users to a chat and notifies every participant, including the
+    /// ones which just joined the chat
+    pub async fn add_participants(
+        &self,
+        user_id: &Uuid,
+        chat_id: &Uuid,
+        participants_ids: &[Uuid],
+    ) -> Result<Chat> {
+        let (chat, added_ids) = self
+            .chat_provider
+            .add_participants(user_id, chat_id, participants_ids)
+            .await?;
+
+        if !added_ids.is_empty() {
+            self.publish_to_participants(
+                &chat.participants_ids,
+                Parcel::ParticipantsAdded(ParticipantsAdded::new(
+                    chat.clone(),
+                    added_ids,
+                    *user_id,
+                )),
+            )
+            .await;
         }
+
+        Ok(chat)
     }
+
+    /// Removes a participant from a chat and notifies the remaining
+    /// participants and the removed one
+    pub async fn remove_participant(
+        &self,
+        user_id: &Uuid,
+        chat_id: &Uuid,
+        participant_id: &Uuid,
+    ) -> Result<Chat> {
+        let (chat, owner_id) = self
+            .chat_provider
+            .remove_participant(user_id, chat_id, participant_id)
+            .await?;
+        let mut notified_ids = chat.participants_ids.clone();
+
+        notified_ids.push(*participant_id);
+
+        self.publish_to_participants(
+            &notified_ids,
+            Parcel::ParticipantRemoved(ParticipantRemoved::new(
+                chat.clone(),
+                *participant_id,
+                *user_id,
+            )),
+        )
+        .await;
+
+        if let Some(owner_id) = owner_id {
+            self.publish_to_participants(
+                &chat.participants_ids,
+                Parcel::RoleChanged(RoleChanged::new(
+                    chat.clone(),
+                    owner_id,
+                    ChatRole::Owner,
+                    *user_id,
+                )),
+            )
+            .await;
+        }
+
+        Ok(chat)
+    }
+
+    /// Updates the role of a participant of a chat and notifies every
+    /// participant of the chat
+    pub async fn update_participant_role(
+        &self,
+        user_id: &Uuid,
+        chat_id: &Uuid,
+        participant_id: &Uuid,
+        role: ChatRole,
+    ) -> Result<Chat> {
+        let chat = self
+            .chat_provider
+            .update_participant_role(user_id, chat_id, participant_id, role)
+            .await?;
+
+        self.publish_to_participants(
+            &chat.participants_ids,
+            Parcel::RoleChanged(RoleChanged::new(
+                chat.clone(),
+                *participant_id,
+                role,
+                *user_id,
+            )),
+        )
+        .await;
+
+        Ok(chat)
+    }
+
+    /// Sends a `Parcel::Mentioned` to every connection of the users mentioned
+    /// in the provided message, but the author
+    async fn publish_mentions(&self, message: &Message) {
+        let mut mentioned_ids: Vec<Uuid> = message
+            .mentions
+            .iter()
+            .map(|mention| mention.user_id)
+            .filter(|user_id| *user_id != message.author.id)
+            .collect();
+
+        mentioned_ids.sort();
+        mentioned_ids.dedup();
+
+        if !mentioned_ids.is_empty() {
+            self.publish_to_participants(&mentioned_ids, Parcel::Mentioned(message.clone()))
+                .await;
+        }
+    }
+
+    /// Sends a `Parcel` to every connection of the provided participants
+    async fn publish_to_participants(&self, participants_ids: &[Uuid], parcel: Parcel) {
+        self.registry
+            .send_to_users(participants_ids, Proto::new_output(parcel), |_| true)
+            .await;
+    }
+
+    /// Publishes an `Proto<Output>` to every connection registered
+    /// in the Hub
+    pub async fn publish(&self, proto: Proto<Output>) {
+        self.registry.broadcast(proto).await;
+    }
+
+    /// Sends an `Proto<Output>` which wraps a `Message` to the connections
+    /// of the chat participants.
+    ///
+    /// The author's browser connections are skipped given that browser
+    /// clients render their own messages as soon as they are sent.
+    pub async fn publish_to_chat(&self, message: Message) {
+        let author_id = message.author.id;
+        let participants_ids = message.chat.participants_ids.clone();
+
+        self.registry
+            .send_to_users(
+                &participants_ids,
+                Proto::new_output(Parcel::LocalMessage(message)),
+                |connection| {
+                    connection.user_id() != author_id
+                        || !matches!(connection.client.frontend, FrontEnd::Browser)
+                },
+            )
+            .await;
+    }
+}
+
+/// Retrieves the IDs of the participants of the provided `chats` excluding
+/// the user with the provided `user_id`
+fn contacts_ids(user_id: &Uuid, chats: &[Chat]) -> Vec<Uuid> {
+    let mut contacts_ids: Vec<Uuid> = chats
+        .iter()
+        .flat_map(|chat| chat.participants_ids.iter().copied())
+        .filter(|participant_id| participant_id != user_id)
+        .collect();
+
+    contacts_ids.sort();
+    contacts_ids.dedup();
+
+    contacts_ids
 }
@@ -1,4 +1,6 @@
 mod chat;
 mod hub;
+mod registry;
+mod typing;
 
 pub use hub::*;
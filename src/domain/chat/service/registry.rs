@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::domain::chat::entity::{Client, Connection, Output, Proto};
+
+/// Registry of the connections (devices) currently attached to the `Hub`
+/// keyed by the `User` which owns them.
+///
+/// Delivering a `Proto<Output>` through the registry never waits for a
+/// connection. Connections which are not able to keep up with their
+/// queue are removed from the registry, which closes their WebSocket.
+#[derive(Default)]
+pub struct ConnectionRegistry {
+    connections: RwLock<HashMap<Uuid, Vec<Connection>>>,
+}
+
+impl ConnectionRegistry {
+    pub fn new() -> Self {
+        Self {
+            connections: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Adds a connection to the registry, retrieving `true` if the
+    /// connection is the first one of its `User`
+    pub async fn register(&self, connection: Connection) -> bool {
+        let mut connections = self.connections.write().await;
+        let user_connections = connections
+            .entry(connection.user_id())
+            .or_insert_with(Vec::new);
+
+        user_connections.push(connection);
+
+        user_connections.len() == 1
+    }
+
+    /// Removes a connection from the registry, the `User` entry is
+    /// removed as well when the connection was its last one
+    pub async fn unregister(&self, user_id: &Uuid, connection_id: &Uuid) {
+        let mut connections = self.connections.write().await;
+
+        if let Some(user_connections) = connections.get_mut(user_id) {
+            user_connections.retain(|connection| connection.id != *connection_id);
+
+            if user_connections.is_empty() {
+                connections.remove(user_id);
+            }
+        }
+    }
+
+    /// Sends a `Proto<Output>` to a single connection of the provided user
+    pub async fn send_to_connection(
+        &self,
+        user_id: &Uuid,
+        connection_id: &Uuid,
+        proto: Proto<Output>,
+    ) {
+        self.send_to_users(&[*user_id], proto, |connection| {
+            connection.id == *connection_id
+        })
+        .await;
+    }
+
+    /// Holds back the output of a connection, as described by
+    /// `Connection::hold`, and retrieves the connection in order to
+    /// release it afterwards
+    pub async fn hold(&self, user_id: &Uuid, connection_id: &Uuid) -> Option<Connection> {
+        let connections = self.connections.read().await;
+        let connection = connections
+            .get(user_id)?
+            .iter()
+            .find(|connection| connection.id == *connection_id)?;
+
+        connection.hold();
+
+        Some(connection.clone())
+    }
+
+    /// Sends a `Proto<Output>` to the connections of the provided users
+    /// which satisfy the `filter` predicate
+    pub async fn send_to_users<F>(&self, users_ids: &[Uuid], proto: Proto<Output>, filter: F)
+    where
+        F: Fn(&Connection) -> bool,
+    {
+        let mut dropped: Vec<(Uuid, Uuid)> = Vec::new();
+
+        {
+            let connections = self.connections.read().await;
+
+            for user_id in users_ids.iter() {
+                if let Some(user_connections) = connections.get(user_id) {
+                    for connection in user_connections.iter().filter(|conn| filter(conn)) {
+                        if let Err(e) = connection.send(proto.clone()) {
+                            warn!("Dropping connection of user {}: {}", user_id, e);
+                            dropped.push((*user_id, connection.id));
+                        }
+                    }
+                }
+            }
+        }
+
+        for (user_id, connection_id) in dropped.iter() {
+            self.unregister(user_id, connection_id).await;
+        }
+    }
+
+    /// Sends a `Proto<Output>` to every registered connection
+    pub async fn broadcast(&self, proto: Proto<Output>) {
+        let users_ids = self.online_users().await;
+
+        self.send_to_users(&users_ids, proto, |_| true).await;
+    }
+
+    /// Retrieves the `Client` of each of the provided users which has at
+    /// least one connection
+    pub async fn online_clients(&self, users_ids: &[Uuid]) -> Vec<Client> {
+        let connections = self.connections.read().await;
+
+        users_ids
+            .iter()
+            .filter_map(|user_id| connections.get(user_id))
+            .filter_map(|user_connections| user_connections.first())
+            .map(|connection| connection.client.clone())
+            .collect()
+    }
+
+    pub async fn is_online(&self, user_id: &Uuid) -> bool {
+        self.connections.read().await.contains_key(user_id)
+    }
+
+    /// Retrieves the IDs of the users with at least one connection
+    pub async fn online_users(&self) -> Vec<Uuid> {
+        self.connections.read().await.keys().copied().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::chat::entity::CONNECTION_QUEUE_CAPACITY;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn it_drops_lagged_connections() {
+        let registry = ConnectionRegistry::new();
+        let client = Client::default();
+        let user_id = client.user_id;
+        let (connection, _output_rx) = Connection::new(client);
+
+        registry.register(connection).await;
+
+        for _ in 0..CONNECTION_QUEUE_CAPACITY {
+            registry.broadcast(Proto::poll_interval()).await;
+        }
+
+        assert_eq!(registry.online_users().await, vec![user_id]);
+
+        registry.broadcast(Proto::poll_interval()).await;
+
+        assert!(registry.online_users().await.is_empty());
+    }
+}
@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Time a typing indicator is kept without being refreshed by the client
+pub const TYPING_EXPIRY: Duration = Duration::from_secs(6);
+
+struct TypingEntry {
+    connection_id: Uuid,
+    expires_at: Instant,
+}
+
+/// Keeps track of the users typing on each chat, keyed by the chat ID and
+/// the user ID.
+///
+/// Typing indicators are never stored, every indicator expires after
+/// `TYPING_EXPIRY` unless it is refreshed by the client which started it.
+#[derive(Default)]
+pub struct TypingTracker {
+    entries: Mutex<HashMap<(Uuid, Uuid), TypingEntry>>,
+}
+
+impl TypingTracker {
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Starts or refreshes the typing indicator of an user on a chat,
+    /// retrieving `true` if the user was not typing already
+    pub fn start(&self, chat_id: Uuid, user_id: Uuid, connection_id: Uuid, now: Instant) -> bool {
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(
+                (chat_id, user_id),
+                TypingEntry {
+                    connection_id,
+                    expires_at: now + TYPING_EXPIRY,
+                },
+            )
+            .is_none()
+    }
+
+    /// Stops the typing indicator of an user on a chat, retrieving `true`
+    /// if the user was typing
+    pub fn stop(&self, chat_id: Uuid, user_id: Uuid) -> bool {
+        self.entries
+            .lock()
+            .unwrap()
+            .remove(&(chat_id, user_id))
+            .is_some()
+    }
+
+    /// Stops the typing indicators started by a connection, retrieving the
+    /// chat ID and user ID of each of them
+    pub fn stop_connection(&self, connection_id: &Uuid) -> Vec<(Uuid, Uuid)> {
+        self.remove_where(|entry| entry.connection_id == *connection_id)
+    }
+
+    /// Stops the typing indicators which expired at `now`, retrieving the
+    /// chat ID and user ID of each of them
+    pub fn expire(&self, now: Instant) -> Vec<(Uuid, Uuid)> {
+        self.remove_where(|entry| entry.expires_at <= now)
+    }
+
+    fn remove_where<F>(&self, predicate: F) -> Vec<(Uuid, Uuid)>
+    where
+        F: Fn(&TypingEntry) -> bool,
+    {
+        let mut entries = self.entries.lock().unwrap();
+        let removed: Vec<(Uuid, Uuid)> = entries
+            .iter()
+            .filter(|(_, entry)| predicate(entry))
+            .map(|(key, _)| *key)
+            .collect();
+
+        for key in removed.iter() {
+            entries.remove(key);
+        }
+
+        removed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_expires_typing_indicators_which_are_not_refreshed() {
+        let tracker = TypingTracker::new();
+        let (chat_id, user_id, connection_id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let now = Instant::now();
+
+        assert!(tracker.start(chat_id, user_id, connection_id, now));
+        assert!(!tracker.start(chat_id, user_id, connection_id, now + TYPING_EXPIRY / 2));
+        assert!(tracker.expire(now + TYPING_EXPIRY).is_empty());
+        assert_eq!(
+            tracker.expire(now + TYPING_EXPIRY * 2),
+            vec![(chat_id, user_id)]
+        );
+        assert!(!tracker.stop(chat_id, user_id));
+    }
+}
@@ -1,8 +1,33 @@
+use lazy_static::lazy_static;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+/// Characters allowed on usernames, as a regular expression character class
+pub const USERNAME_CHARS: &str = "a-z0-9.";
+
+/// Min amount of characters of an username
+pub const USERNAME_MIN_LENGTH: usize = 7;
+
+/// Max amount of characters of an username
+pub const USERNAME_MAX_LENGTH: usize = 20;
+
+lazy_static! {
+    static ref USERNAME_REGEX: Regex = Regex::new(&format!(
+        "^[{}]{{{},{}}}$",
+        USERNAME_CHARS, USERNAME_MIN_LENGTH, USERNAME_MAX_LENGTH
+    ))
+    .unwrap();
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct User {
     pub id: Uuid,
     pub name: String,
 }
+
+/// Checks the provided `name` is made of `USERNAME_CHARS` and has between
+/// `USERNAME_MIN_LENGTH` and `USERNAME_MAX_LENGTH` characters
+pub fn is_valid_username(name: &str) -> bool {
+    USERNAME_REGEX.is_match(name)
+}
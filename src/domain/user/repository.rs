@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use sqlx::{Postgres, Transaction};
 use uuid::Uuid;
 
@@ -15,4 +16,6 @@ pub trait UserRepository {
     ) -> Result<User>;
     async fn find_one(&self, id: &Uuid) -> Result<User>;
     async fn find_by_name(&self, name: &str) -> Result<User>;
+    async fn touch_last_seen(&self, id: &Uuid) -> Result<DateTime<Utc>>;
+    async fn fetch_last_seen(&self, ids: &[Uuid]) -> Result<Vec<(Uuid, Option<DateTime<Utc>>)>>;
 }
@@ -1,5 +1,4 @@
-use lazy_static::lazy_static;
-use regex::Regex;
+use chrono::{DateTime, Utc};
 use sqlx::postgres::Postgres;
 use sqlx::Transaction;
 use std::sync::Arc;
@@ -10,11 +9,7 @@ use crate::domain::secret::{SecretRepository, SecretService};
 use crate::error::{Error, Result};
 use crate::infrastructure::repository::base::BaseRepository;
 
-use super::{User, UserRepository};
-
-lazy_static! {
-    static ref USERNAME_REGEX: Regex = Regex::new("^[a-z0-9.]{7,20}$").unwrap();
-}
+use super::{is_valid_username, User, UserRepository};
 
 pub struct UserService<R, S, T>
 where
@@ -68,7 +63,7 @@ where
         tx: &mut Transaction<'static, Postgres>,
         name: &str,
     ) -> Result<User> {
-        if !USERNAME_REGEX.is_match(name) {
+        if !is_valid_username(name) {
             return Err(Error::InvalidUsername(name.to_string()));
         }
 
@@ -85,6 +80,21 @@ where
         self.user_repository.find_one(id).await
     }
 
+    /// Updates the last time the user with the provided `id` was seen
+    /// online to the current time
+    pub async fn update_last_seen(&self, id: &Uuid) -> Result<DateTime<Utc>> {
+        self.user_repository.touch_last_seen(id).await
+    }
+
+    /// Fetches the last time each of the users with the provided `ids` was
+    /// seen online, users who were never seen online have no timestamp
+    pub async fn fetch_last_seen(
+        &self,
+        ids: &[Uuid],
+    ) -> Result<Vec<(Uuid, Option<DateTime<Utc>>)>> {
+        self.user_repository.fetch_last_seen(ids).await
+    }
+
     pub async fn fetch_profile(&self, id: &Uuid) -> Result<Profile> {
         self.profile_service.find_by_user_id(id).await
     }
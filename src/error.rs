@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use http_auth_basic::AuthBasicError;
 use image::ImageError;
 use sqlx::error::Error as SqlxError;
@@ -55,14 +56,66 @@ pub enum Error {
     JsonParsingError(String),
     #[error("Not enough participants to create a Chat. You must provide at least 2 participants, provided {0}")]
     ChatNotEnoughParticipants(u8),
+    #[error("User with ID: {0} must be one of the participants of the Chat being created")]
+    ChatCreatorNotParticipant(Uuid),
+    #[error("User with ID: {0} is not a participant of the Chat with ID: {1}")]
+    ParticipantNotFound(Uuid, Uuid),
+    #[error("User with ID: {0} is not allowed to {2} in the Chat with ID: {1}")]
+    ChatActionNotAllowed(Uuid, Uuid, String),
+    #[error("Invalid chat kind provided, {0}")]
+    InvalidChatKind(String),
+    #[error("Chat with ID: {0} is a direct chat which can't be modified")]
+    DirectChatNotModifiable(Uuid),
+    #[error("A direct chat must be started with another user")]
+    DirectChatWithSelf,
+    #[error("Invalid chat role provided, {0}")]
+    InvalidChatRole(String),
     #[error("Chat doesn't exists")]
     ChatNotFound,
     #[error("User with ID: {0} doesn't exists in Chat with ID: {1}")]
     UserDoesntBelongToChat(Uuid, Uuid),
+    #[error("Message author with ID: {0} doesn't match the authenticated user with ID: {1}")]
+    AuthorMismatch(Uuid, Uuid),
+    #[error("Client message ID: {0} was already used on another chat")]
+    DuplicateClientMessageId(Uuid),
+    #[error(
+        "Invalid amount of messages requested, expected a value between 1 and {1}, provided {0}"
+    )]
+    InvalidMessagesLimit(i64, i64),
+    #[error("Invalid search, {0}")]
+    InvalidSearchQuery(String),
+    #[error("Message with ID: {0} doesn't exists")]
+    MessageNotFound(Uuid),
+    #[error("Scheduled message with ID: {0} doesn't exists")]
+    ScheduledMessageNotFound(Uuid),
+    #[error("User with ID: {0} is not the author of the Message with ID: {1}")]
+    NotMessageAuthor(Uuid, Uuid),
+    #[error("Invalid message kind provided, {0}")]
+    InvalidMessageKind(String),
+    #[error("Messages of kind {0} must reference a file")]
+    MissingAttachment(String),
+    #[error("Text messages can't reference a file")]
+    UnexpectedAttachment,
+    #[error("File with ID: {0} doesn't exists or wasn't uploaded by the message author")]
+    AttachmentNotFound(Uuid),
+    #[error("File with ID: {0} is not an image")]
+    AttachmentNotImage(Uuid),
+    #[error("Message with ID: {0} can't be replied, it doesn't exists in the chat")]
+    InvalidReplyTo(Uuid),
+    #[error("Invalid reaction provided, {0}")]
+    InvalidReaction(String),
+    #[error("Invalid message TTL: {0}, TTLs must be between 1 second and 1 year")]
+    InvalidMessageTtl(i32),
+    #[error("Messages can't be scheduled at {0}, schedule messages within the next year")]
+    InvalidSendAt(DateTime<Utc>),
     #[error("Store message error!")]
     UnableToStoreMessage,
     #[error("Invalid frontend for chat provided, {0}")]
     InvalidFrontEnd(String),
+    #[error("Connection with ID: {0} is not consuming its output fast enough")]
+    ConnectionLagged(Uuid),
+    #[error("Connection with ID: {0} is closed")]
+    ConnectionClosed(Uuid),
 }
 
 impl Reject for Error {}
@@ -1,6 +1,8 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
 use sqlx::postgres::Postgres;
-use sqlx::Transaction;
+use sqlx::{Row, Transaction};
 use uuid::Uuid;
 
 use crate::domain::user::{User, UserRepository};
@@ -43,20 +45,44 @@ impl UserRepository for Repository {
     }
 
     async fn find_one(&self, id: &Uuid) -> Result<User> {
-        let user: UserDTO = sqlx::query_as("SELECT * FROM users WHERE id = $1")
+        let user: Option<UserDTO> = sqlx::query_as("SELECT * FROM users WHERE id = $1")
             .bind(id)
-            .fetch_one(self.db_pool)
+            .fetch_optional(self.db_pool)
             .await?;
 
-        Ok(user.into())
+        user.map(UserDTO::into).ok_or(Error::UserNotFound)
     }
 
     async fn find_by_name(&self, name: &str) -> Result<User> {
-        let user: UserDTO = sqlx::query_as("SELECT * FROM users WHERE name = $1")
+        let user: Option<UserDTO> = sqlx::query_as("SELECT * FROM users WHERE name = $1")
             .bind(name)
-            .fetch_one(self.db_pool)
+            .fetch_optional(self.db_pool)
             .await?;
 
-        Ok(user.into())
+        user.map(UserDTO::into).ok_or(Error::UserNotFound)
+    }
+
+    async fn touch_last_seen(&self, id: &Uuid) -> Result<DateTime<Utc>> {
+        let row = sqlx::query(
+            "UPDATE users SET last_seen_at = CURRENT_TIMESTAMP WHERE id = $1 RETURNING last_seen_at",
+        )
+        .bind(id)
+        .fetch_one(self.db_pool)
+        .await?;
+
+        Ok(row.try_get("last_seen_at")?)
+    }
+
+    async fn fetch_last_seen(&self, ids: &[Uuid]) -> Result<Vec<(Uuid, Option<DateTime<Utc>>)>> {
+        let mut last_seen: Vec<(Uuid, Option<DateTime<Utc>>)> = Vec::new();
+        let mut rows = sqlx::query("SELECT id, last_seen_at FROM users WHERE id = ANY($1)")
+            .bind(ids)
+            .fetch(self.db_pool);
+
+        while let Some(row) = rows.try_next().await? {
+            last_seen.push((row.try_get("id")?, row.try_get("last_seen_at")?));
+        }
+
+        Ok(last_seen)
     }
 }
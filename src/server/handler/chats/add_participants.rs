@@ -0,0 +1,36 @@
+use serde::Deserialize;
+use uuid::Uuid;
+use warp::http::StatusCode;
+use warp::reject::Rejection;
+
+use crate::application::service::Services;
+use crate::domain::auth::Claims;
+use crate::domain::chat::Chat;
+use crate::error::Error;
+use crate::server::utils::Response;
+
+#[derive(Deserialize)]
+pub struct AddParticipantsPayload {
+    participants_ids: Vec<Uuid>,
+}
+
+pub async fn add_participants(
+    claims: Claims,
+    chat: Chat,
+    services: Services,
+    payload: AddParticipantsPayload,
+) -> Result<impl warp::Reply, Rejection> {
+    match services
+        .hub_service
+        .add_participants(&claims.user_id, &chat.id, &payload.participants_ids)
+        .await
+    {
+        Ok(chat) => Ok(Response::new(chat).status_code(StatusCode::OK)),
+        Err(e @ Error::DirectChatNotModifiable(_)) => {
+            Err(Response::reject_with(e, StatusCode::CONFLICT))
+        }
+        Err(e) => Err(Response::message(e.to_string())
+            .status_code(StatusCode::BAD_REQUEST)
+            .reject()),
+    }
+}
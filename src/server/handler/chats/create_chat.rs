@@ -5,25 +5,32 @@ use warp::reject::Rejection;
 
 use crate::application::service::Services;
 use crate::domain::auth::Claims;
+use crate::domain::chat::ChatDetailsDTO;
+use crate::error::Error;
 use crate::server::utils::Response;
 
 #[derive(Deserialize)]
 pub struct CreateChatPayload {
     participants_ids: Vec<Uuid>,
+    #[serde(flatten)]
+    details: ChatDetailsDTO,
 }
 
 pub async fn create_chat(
-    _: Claims,
+    claims: Claims,
     services: Services,
     payload: CreateChatPayload,
 ) -> Result<impl warp::Reply, Rejection> {
     match services
         .hub_service
         .chat_provider
-        .create_chat(payload.participants_ids)
+        .create_chat(&claims.user_id, payload.participants_ids, payload.details)
         .await
     {
         Ok(chat) => Ok(Response::new(chat).status_code(StatusCode::CREATED)),
+        Err(e @ Error::ChatCreatorNotParticipant(_)) => {
+            Err(Response::reject_with(e, StatusCode::FORBIDDEN))
+        }
         Err(e) => Err(Response::message(e.to_string())
             .status_code(StatusCode::BAD_REQUEST)
             .reject()),
@@ -0,0 +1,31 @@
+use uuid::Uuid;
+use warp::http::StatusCode;
+use warp::reject::Rejection;
+
+use crate::application::service::Services;
+use crate::domain::auth::Claims;
+use crate::domain::chat::Chat;
+use crate::error::Error;
+use crate::server::utils::Response;
+
+pub async fn delete_message(
+    claims: Claims,
+    chat: Chat,
+    services: Services,
+    message_id: Uuid,
+) -> Result<impl warp::Reply, Rejection> {
+    match services
+        .hub_service
+        .delete_message(&claims.user_id, &chat.id, &message_id)
+        .await
+    {
+        Ok(message) => Ok(Response::new(message).status_code(StatusCode::OK)),
+        Err(e @ Error::MessageNotFound(_)) => Err(Response::reject_with(e, StatusCode::NOT_FOUND)),
+        Err(e @ Error::NotMessageAuthor(_, _)) => {
+            Err(Response::reject_with(e, StatusCode::FORBIDDEN))
+        }
+        Err(e) => Err(Response::message(e.to_string())
+            .status_code(StatusCode::BAD_REQUEST)
+            .reject()),
+    }
+}
@@ -1,32 +1,69 @@
-use serde::Serialize;
-use uuid::Uuid;
+use serde::{Deserialize, Serialize};
 use warp::http::StatusCode;
 use warp::reject::Rejection;
 
 use crate::application::service::Services;
 use crate::domain::auth::Claims;
-use crate::domain::chat::Message;
+use crate::domain::chat::{Chat, Message, MessagesCursor};
 use crate::server::utils::Response;
 
+/// Amount of messages retrieved when no `limit` is provided
+const DEFAULT_MESSAGES_LIMIT: i64 = 50;
+
+/// Query parameters expected by the `/chats/:chat_id/messages`
+/// endpoint.
+///
+/// `before` and `after` are the `previous` and `next` cursors
+/// retrieved on a `FetchChatMessagesResponse`
+#[derive(Deserialize)]
+pub struct FetchChatMessagesQueryParams {
+    before: Option<i64>,
+    after: Option<i64>,
+    limit: Option<i64>,
+}
+
 #[derive(Serialize)]
 pub struct FetchChatMessagesResponse {
     messages: Vec<Message>,
+    previous: Option<i64>,
+    next: Option<i64>,
 }
 
 pub async fn fetch_chat_messages(
     _: Claims,
+    chat: Chat,
     services: Services,
-    chat_id: Uuid,
+    qparams: FetchChatMessagesQueryParams,
 ) -> Result<impl warp::Reply, Rejection> {
+    let cursor = match (qparams.before, qparams.after) {
+        (None, None) => MessagesCursor::Latest,
+        (Some(before), None) => MessagesCursor::Before(before),
+        (None, Some(after)) => MessagesCursor::After(after),
+        (Some(_), Some(_)) => {
+            return Err(Response::message(String::from(
+                "Only one of \"before\" and \"after\" query parameters must be provided",
+            ))
+            .status_code(StatusCode::BAD_REQUEST)
+            .reject())
+        }
+    };
+
     match services
         .hub_service
         .chat_provider
-        .fetch_chat_messages(&chat_id)
+        .fetch_chat_messages(
+            &chat,
+            cursor,
+            qparams.limit.unwrap_or(DEFAULT_MESSAGES_LIMIT),
+        )
         .await
     {
-        Ok(messages) => {
-            Ok(Response::new(FetchChatMessagesResponse { messages }).status_code(StatusCode::OK))
-        }
+        Ok(page) => Ok(Response::new(FetchChatMessagesResponse {
+            messages: page.messages,
+            previous: page.previous,
+            next: page.next,
+        })
+        .status_code(StatusCode::OK)),
         Err(e) => Err(Response::message(e.to_string())
             .status_code(StatusCode::BAD_REQUEST)
             .reject()),
@@ -0,0 +1,49 @@
+use serde::Deserialize;
+use uuid::Uuid;
+use warp::http::StatusCode;
+use warp::reject::Rejection;
+
+use crate::application::service::Services;
+use crate::domain::auth::Claims;
+use crate::domain::chat::Chat;
+use crate::error::Error;
+use crate::server::utils::Response;
+
+/// Amount of replies retrieved when no `limit` is provided
+const DEFAULT_REPLIES_LIMIT: i64 = 50;
+
+/// Query parameters expected by the
+/// `/chats/:chat_id/messages/:message_id/thread` endpoint.
+///
+/// `after` is the `next` cursor retrieved on a previous `Thread`
+#[derive(Deserialize)]
+pub struct FetchThreadQueryParams {
+    after: Option<i64>,
+    limit: Option<i64>,
+}
+
+pub async fn fetch_thread(
+    _: Claims,
+    chat: Chat,
+    services: Services,
+    message_id: Uuid,
+    qparams: FetchThreadQueryParams,
+) -> Result<impl warp::Reply, Rejection> {
+    match services
+        .hub_service
+        .chat_provider
+        .fetch_thread(
+            &chat,
+            &message_id,
+            qparams.after,
+            qparams.limit.unwrap_or(DEFAULT_REPLIES_LIMIT),
+        )
+        .await
+    {
+        Ok(thread) => Ok(Response::new(thread).status_code(StatusCode::OK)),
+        Err(e @ Error::MessageNotFound(_)) => Err(Response::reject_with(e, StatusCode::NOT_FOUND)),
+        Err(e) => Err(Response::message(e.to_string())
+            .status_code(StatusCode::BAD_REQUEST)
+            .reject()),
+    }
+}
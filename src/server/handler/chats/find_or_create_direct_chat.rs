@@ -0,0 +1,48 @@
+use serde::Deserialize;
+use uuid::Uuid;
+use warp::http::StatusCode;
+use warp::reject::Rejection;
+
+use crate::application::service::Services;
+use crate::domain::auth::Claims;
+use crate::error::Error;
+use crate::server::utils::Response;
+
+#[derive(Deserialize)]
+pub struct FindOrCreateDirectChatPayload {
+    user_id: Uuid,
+}
+
+pub async fn find_or_create_direct_chat(
+    claims: Claims,
+    services: Services,
+    payload: FindOrCreateDirectChatPayload,
+) -> Result<impl warp::Reply, Rejection> {
+    match services.user_service.find_by_id(&payload.user_id).await {
+        Ok(_) => {}
+        Err(e @ Error::UserNotFound) => {
+            return Err(Response::reject_with(e, StatusCode::NOT_FOUND))
+        }
+        Err(e) => {
+            return Err(Response::message(e.to_string())
+                .status_code(StatusCode::INTERNAL_SERVER_ERROR)
+                .reject())
+        }
+    }
+
+    match services
+        .hub_service
+        .chat_provider
+        .find_or_create_direct_chat(&claims.user_id, &payload.user_id)
+        .await
+    {
+        Ok((chat, true)) => Ok(Response::new(chat).status_code(StatusCode::CREATED)),
+        Ok((chat, false)) => Ok(Response::new(chat).status_code(StatusCode::OK)),
+        Err(e @ Error::DirectChatWithSelf) => {
+            Err(Response::reject_with(e, StatusCode::BAD_REQUEST))
+        }
+        Err(e) => Err(Response::message(e.to_string())
+            .status_code(StatusCode::INTERNAL_SERVER_ERROR)
+            .reject()),
+    }
+}
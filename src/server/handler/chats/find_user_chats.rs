@@ -1,25 +1,32 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use warp::http::StatusCode;
 use warp::reject::Rejection;
 
 use crate::application::service::Services;
 use crate::domain::auth::Claims;
-use crate::domain::chat::Chat;
+use crate::domain::chat::ChatSummary;
 use crate::server::utils::Response;
 
+#[derive(Deserialize)]
+pub struct FindUserChatsQueryParams {
+    #[serde(default)]
+    archived: bool,
+}
+
 #[derive(Serialize)]
 pub struct FindUserChatsResponse {
-    chats: Vec<Chat>,
+    chats: Vec<ChatSummary>,
 }
 
 pub async fn find_user_chats(
     claims: Claims,
     services: Services,
+    qparams: FindUserChatsQueryParams,
 ) -> Result<impl warp::Reply, Rejection> {
     match services
         .hub_service
         .chat_provider
-        .fetch_chats(&claims.user_id)
+        .fetch_chat_summaries(&claims.user_id, qparams.archived)
         .await
     {
         Ok(chats) => Ok(Response::new(FindUserChatsResponse { chats }).status_code(StatusCode::OK)),
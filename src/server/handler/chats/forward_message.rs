@@ -0,0 +1,40 @@
+use serde::Deserialize;
+use uuid::Uuid;
+use warp::http::StatusCode;
+use warp::reject::Rejection;
+
+use crate::application::service::Services;
+use crate::domain::auth::Claims;
+use crate::domain::chat::Chat;
+use crate::error::Error;
+use crate::server::utils::Response;
+
+#[derive(Deserialize)]
+pub struct ForwardMessagePayload {
+    chat_id: Uuid,
+}
+
+pub async fn forward_message(
+    claims: Claims,
+    chat: Chat,
+    services: Services,
+    message_id: Uuid,
+    payload: ForwardMessagePayload,
+) -> Result<impl warp::Reply, Rejection> {
+    match services
+        .hub_service
+        .forward_message(&claims.user_id, &chat.id, &message_id, &payload.chat_id)
+        .await
+    {
+        Ok(message) => Ok(Response::new(message).status_code(StatusCode::CREATED)),
+        Err(e @ Error::MessageNotFound(_)) | Err(e @ Error::ChatNotFound) => {
+            Err(Response::reject_with(e, StatusCode::NOT_FOUND))
+        }
+        Err(e @ Error::UserDoesntBelongToChat(_, _)) => {
+            Err(Response::reject_with(e, StatusCode::FORBIDDEN))
+        }
+        Err(e) => Err(Response::message(e.to_string())
+            .status_code(StatusCode::BAD_REQUEST)
+            .reject()),
+    }
+}
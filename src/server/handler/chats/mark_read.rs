@@ -0,0 +1,31 @@
+use serde::Deserialize;
+use warp::http::StatusCode;
+use warp::reject::Rejection;
+
+use crate::application::service::Services;
+use crate::domain::auth::Claims;
+use crate::domain::chat::Chat;
+use crate::server::utils::Response;
+
+#[derive(Deserialize)]
+pub struct MarkReadPayload {
+    sequence: i64,
+}
+
+pub async fn mark_read(
+    claims: Claims,
+    chat: Chat,
+    services: Services,
+    payload: MarkReadPayload,
+) -> Result<impl warp::Reply, Rejection> {
+    match services
+        .hub_service
+        .mark_read(&claims.user_id, &chat.id, payload.sequence)
+        .await
+    {
+        Ok(receipt) => Ok(Response::new(receipt).status_code(StatusCode::OK)),
+        Err(e) => Err(Response::message(e.to_string())
+            .status_code(StatusCode::BAD_REQUEST)
+            .reject()),
+    }
+}
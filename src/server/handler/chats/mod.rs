@@ -1,9 +1,41 @@
+mod add_participants;
 mod create_chat;
+mod delete_message;
+mod edit_message;
 mod fetch_chat_messages;
+mod fetch_thread;
 mod find_chat;
+mod find_or_create_direct_chat;
 mod find_user_chats;
+mod forward_message;
+mod mark_read;
+mod pins;
+mod reactions;
+mod remove_participant;
+mod scheduled_messages;
+mod update_chat;
+mod update_chat_settings;
+mod update_message_ttl;
+mod update_participant_role;
+mod upload_chat_avatar;
 
+pub use add_participants::*;
 pub use create_chat::*;
+pub use delete_message::*;
+pub use edit_message::*;
 pub use fetch_chat_messages::*;
+pub use fetch_thread::*;
 pub use find_chat::*;
+pub use find_or_create_direct_chat::*;
 pub use find_user_chats::*;
+pub use forward_message::*;
+pub use mark_read::*;
+pub use pins::*;
+pub use reactions::*;
+pub use remove_participant::*;
+pub use scheduled_messages::*;
+pub use update_chat::*;
+pub use update_chat_settings::*;
+pub use update_message_ttl::*;
+pub use update_participant_role::*;
+pub use upload_chat_avatar::*;
@@ -0,0 +1,71 @@
+use serde::Serialize;
+use uuid::Uuid;
+use warp::http::StatusCode;
+use warp::reject::Rejection;
+
+use crate::application::service::Services;
+use crate::domain::auth::Claims;
+use crate::domain::chat::{Chat, Pin};
+use crate::error::Error;
+use crate::server::utils::Response;
+
+#[derive(Serialize)]
+pub struct FetchPinsResponse {
+    pins: Vec<Pin>,
+}
+
+pub async fn fetch_pins(
+    _: Claims,
+    chat: Chat,
+    services: Services,
+) -> Result<impl warp::Reply, Rejection> {
+    match services.hub_service.chat_provider.fetch_pins(&chat).await {
+        Ok(pins) => Ok(Response::new(FetchPinsResponse { pins }).status_code(StatusCode::OK)),
+        Err(e) => Err(Response::message(e.to_string())
+            .status_code(StatusCode::BAD_REQUEST)
+            .reject()),
+    }
+}
+
+pub async fn pin_message(
+    claims: Claims,
+    chat: Chat,
+    services: Services,
+    message_id: Uuid,
+) -> Result<impl warp::Reply, Rejection> {
+    match services
+        .hub_service
+        .pin_message(&claims.user_id, &chat.id, &message_id)
+        .await
+    {
+        Ok(pin) => Ok(Response::new(pin).status_code(StatusCode::OK)),
+        Err(e @ Error::MessageNotFound(_)) => Err(Response::reject_with(e, StatusCode::NOT_FOUND)),
+        Err(e @ Error::ChatActionNotAllowed(_, _, _)) => {
+            Err(Response::reject_with(e, StatusCode::FORBIDDEN))
+        }
+        Err(e) => Err(Response::message(e.to_string())
+            .status_code(StatusCode::BAD_REQUEST)
+            .reject()),
+    }
+}
+
+pub async fn unpin_message(
+    claims: Claims,
+    chat: Chat,
+    services: Services,
+    message_id: Uuid,
+) -> Result<impl warp::Reply, Rejection> {
+    match services
+        .hub_service
+        .unpin_message(&claims.user_id, &chat.id, &message_id)
+        .await
+    {
+        Ok(unpin) => Ok(Response::new(unpin).status_code(StatusCode::OK)),
+        Err(e @ Error::ChatActionNotAllowed(_, _, _)) => {
+            Err(Response::reject_with(e, StatusCode::FORBIDDEN))
+        }
+        Err(e) => Err(Response::message(e.to_string())
+            .status_code(StatusCode::BAD_REQUEST)
+            .reject()),
+    }
+}
@@ -0,0 +1,55 @@
+use serde::Deserialize;
+use uuid::Uuid;
+use warp::http::StatusCode;
+use warp::reject::Rejection;
+
+use crate::application::service::Services;
+use crate::domain::auth::Claims;
+use crate::domain::chat::Chat;
+use crate::error::Error;
+use crate::server::utils::Response;
+
+#[derive(Deserialize)]
+pub struct ReactionPayload {
+    emoji: String,
+}
+
+pub async fn add_reaction(
+    claims: Claims,
+    chat: Chat,
+    services: Services,
+    message_id: Uuid,
+    payload: ReactionPayload,
+) -> Result<impl warp::Reply, Rejection> {
+    match services
+        .hub_service
+        .add_reaction(&claims.user_id, &chat.id, &message_id, &payload.emoji)
+        .await
+    {
+        Ok(reaction) => Ok(Response::new(reaction).status_code(StatusCode::OK)),
+        Err(e @ Error::MessageNotFound(_)) => Err(Response::reject_with(e, StatusCode::NOT_FOUND)),
+        Err(e) => Err(Response::message(e.to_string())
+            .status_code(StatusCode::BAD_REQUEST)
+            .reject()),
+    }
+}
+
+pub async fn remove_reaction(
+    claims: Claims,
+    chat: Chat,
+    services: Services,
+    message_id: Uuid,
+    payload: ReactionPayload,
+) -> Result<impl warp::Reply, Rejection> {
+    match services
+        .hub_service
+        .remove_reaction(&claims.user_id, &chat.id, &message_id, &payload.emoji)
+        .await
+    {
+        Ok(reaction) => Ok(Response::new(reaction).status_code(StatusCode::OK)),
+        Err(e @ Error::MessageNotFound(_)) => Err(Response::reject_with(e, StatusCode::NOT_FOUND)),
+        Err(e) => Err(Response::message(e.to_string())
+            .status_code(StatusCode::BAD_REQUEST)
+            .reject()),
+    }
+}
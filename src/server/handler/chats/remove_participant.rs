@@ -0,0 +1,46 @@
+use uuid::Uuid;
+use warp::http::StatusCode;
+use warp::reject::Rejection;
+
+use crate::application::service::Services;
+use crate::domain::auth::Claims;
+use crate::domain::chat::Chat;
+use crate::error::Error;
+use crate::server::utils::Response;
+
+pub async fn remove_participant(
+    claims: Claims,
+    chat: Chat,
+    services: Services,
+    participant_id: Uuid,
+) -> Result<impl warp::Reply, Rejection> {
+    match services
+        .hub_service
+        .remove_participant(&claims.user_id, &chat.id, &participant_id)
+        .await
+    {
+        Ok(chat) => Ok(Response::new(chat).status_code(StatusCode::OK)),
+        Err(e @ Error::DirectChatNotModifiable(_)) => {
+            Err(Response::reject_with(e, StatusCode::CONFLICT))
+        }
+        Err(e @ Error::ChatActionNotAllowed(_, _, _)) => {
+            Err(Response::reject_with(e, StatusCode::FORBIDDEN))
+        }
+        Err(e @ Error::ParticipantNotFound(_, _)) => {
+            Err(Response::reject_with(e, StatusCode::NOT_FOUND))
+        }
+        Err(e) => Err(Response::message(e.to_string())
+            .status_code(StatusCode::BAD_REQUEST)
+            .reject()),
+    }
+}
+
+pub async fn leave_chat(
+    claims: Claims,
+    chat: Chat,
+    services: Services,
+) -> Result<impl warp::Reply, Rejection> {
+    let participant_id = claims.user_id;
+
+    remove_participant(claims, chat, services, participant_id).await
+}
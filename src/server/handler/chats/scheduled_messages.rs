@@ -0,0 +1,104 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use warp::http::StatusCode;
+use warp::reject::Rejection;
+
+use crate::application::service::Services;
+use crate::domain::auth::Claims;
+use crate::domain::chat::{Chat, InputProtoMessageDTO, MessageKind, ScheduledMessage};
+use crate::error::Error;
+use crate::server::utils::Response;
+
+#[derive(Deserialize)]
+pub struct ScheduleMessagePayload {
+    body: String,
+    #[serde(default)]
+    kind: MessageKind,
+    #[serde(default)]
+    file_id: Option<Uuid>,
+    #[serde(default)]
+    reply_to: Option<Uuid>,
+    send_at: DateTime<Utc>,
+}
+
+#[derive(Serialize)]
+pub struct FetchScheduledMessagesResponse {
+    scheduled_messages: Vec<ScheduledMessage>,
+}
+
+pub async fn schedule_message(
+    claims: Claims,
+    chat: Chat,
+    services: Services,
+    payload: ScheduleMessagePayload,
+) -> Result<impl warp::Reply, Rejection> {
+    let incoming_message = InputProtoMessageDTO {
+        author_id: None,
+        chat_id: chat.id,
+        body: payload.body,
+        client_message_id: None,
+        kind: payload.kind,
+        file_id: payload.file_id,
+        reply_to: payload.reply_to,
+    };
+
+    match services
+        .hub_service
+        .chat_provider
+        .schedule_message(&claims.user_id, incoming_message, payload.send_at)
+        .await
+    {
+        Ok(scheduled_message) => {
+            Ok(Response::new(scheduled_message).status_code(StatusCode::CREATED))
+        }
+        Err(e) => Err(Response::message(e.to_string())
+            .status_code(StatusCode::BAD_REQUEST)
+            .reject()),
+    }
+}
+
+pub async fn fetch_scheduled_messages(
+    claims: Claims,
+    chat: Chat,
+    services: Services,
+) -> Result<impl warp::Reply, Rejection> {
+    match services
+        .hub_service
+        .chat_provider
+        .fetch_scheduled_messages(&claims.user_id, &chat)
+        .await
+    {
+        Ok(scheduled_messages) => {
+            Ok(
+                Response::new(FetchScheduledMessagesResponse { scheduled_messages })
+                    .status_code(StatusCode::OK),
+            )
+        }
+        Err(e) => Err(Response::message(e.to_string())
+            .status_code(StatusCode::BAD_REQUEST)
+            .reject()),
+    }
+}
+
+pub async fn cancel_scheduled_message(
+    claims: Claims,
+    chat: Chat,
+    services: Services,
+    scheduled_message_id: Uuid,
+) -> Result<impl warp::Reply, Rejection> {
+    match services
+        .hub_service
+        .chat_provider
+        .cancel_scheduled_message(&claims.user_id, &chat, &scheduled_message_id)
+        .await
+    {
+        Ok(scheduled_message) => Ok(Response::new(scheduled_message).status_code(StatusCode::OK)),
+        Err(e @ Error::ScheduledMessageNotFound(_)) => {
+            Err(Response::reject_with(e, StatusCode::NOT_FOUND))
+        }
+        Err(e) => Err(Response::message(e.to_string())
+            .status_code(StatusCode::BAD_REQUEST)
+            .reject()),
+    }
+}
@@ -0,0 +1,32 @@
+use warp::http::StatusCode;
+use warp::reject::Rejection;
+
+use crate::application::service::Services;
+use crate::domain::auth::Claims;
+use crate::domain::chat::{Chat, ChatDetailsDTO};
+use crate::error::Error;
+use crate::server::utils::Response;
+
+pub async fn update_chat(
+    claims: Claims,
+    chat: Chat,
+    services: Services,
+    payload: ChatDetailsDTO,
+) -> Result<impl warp::Reply, Rejection> {
+    match services
+        .hub_service
+        .update_chat(&claims.user_id, &chat.id, payload)
+        .await
+    {
+        Ok(chat) => Ok(Response::new(chat).status_code(StatusCode::OK)),
+        Err(e @ Error::DirectChatNotModifiable(_)) => {
+            Err(Response::reject_with(e, StatusCode::CONFLICT))
+        }
+        Err(e @ Error::ChatActionNotAllowed(_, _, _)) => {
+            Err(Response::reject_with(e, StatusCode::FORBIDDEN))
+        }
+        Err(e) => Err(Response::message(e.to_string())
+            .status_code(StatusCode::BAD_REQUEST)
+            .reject()),
+    }
+}
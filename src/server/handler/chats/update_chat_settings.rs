@@ -0,0 +1,25 @@
+use warp::http::StatusCode;
+use warp::reject::Rejection;
+
+use crate::application::service::Services;
+use crate::domain::auth::Claims;
+use crate::domain::chat::{Chat, ChatSettingsDTO};
+use crate::server::utils::Response;
+
+pub async fn update_chat_settings(
+    claims: Claims,
+    chat: Chat,
+    services: Services,
+    payload: ChatSettingsDTO,
+) -> Result<impl warp::Reply, Rejection> {
+    match services
+        .hub_service
+        .update_chat_settings(&claims.user_id, &chat.id, payload)
+        .await
+    {
+        Ok(settings) => Ok(Response::new(settings).status_code(StatusCode::OK)),
+        Err(e) => Err(Response::message(e.to_string())
+            .status_code(StatusCode::BAD_REQUEST)
+            .reject()),
+    }
+}
@@ -0,0 +1,36 @@
+use serde::Deserialize;
+use warp::http::StatusCode;
+use warp::reject::Rejection;
+
+use crate::application::service::Services;
+use crate::domain::auth::Claims;
+use crate::domain::chat::Chat;
+use crate::error::Error;
+use crate::server::utils::Response;
+
+#[derive(Deserialize)]
+pub struct UpdateMessageTtlPayload {
+    #[serde(default)]
+    message_ttl: Option<i32>,
+}
+
+pub async fn update_message_ttl(
+    claims: Claims,
+    chat: Chat,
+    services: Services,
+    payload: UpdateMessageTtlPayload,
+) -> Result<impl warp::Reply, Rejection> {
+    match services
+        .hub_service
+        .update_message_ttl(&claims.user_id, &chat.id, payload.message_ttl)
+        .await
+    {
+        Ok(chat) => Ok(Response::new(chat).status_code(StatusCode::OK)),
+        Err(e @ Error::ChatActionNotAllowed(_, _, _)) => {
+            Err(Response::reject_with(e, StatusCode::FORBIDDEN))
+        }
+        Err(e) => Err(Response::message(e.to_string())
+            .status_code(StatusCode::BAD_REQUEST)
+            .reject()),
+    }
+}
@@ -0,0 +1,43 @@
+use serde::Deserialize;
+use uuid::Uuid;
+use warp::http::StatusCode;
+use warp::reject::Rejection;
+
+use crate::application::service::Services;
+use crate::domain::auth::Claims;
+use crate::domain::chat::{Chat, ChatRole};
+use crate::error::Error;
+use crate::server::utils::Response;
+
+#[derive(Deserialize)]
+pub struct UpdateParticipantRolePayload {
+    role: ChatRole,
+}
+
+pub async fn update_participant_role(
+    claims: Claims,
+    chat: Chat,
+    services: Services,
+    participant_id: Uuid,
+    payload: UpdateParticipantRolePayload,
+) -> Result<impl warp::Reply, Rejection> {
+    match services
+        .hub_service
+        .update_participant_role(&claims.user_id, &chat.id, &participant_id, payload.role)
+        .await
+    {
+        Ok(chat) => Ok(Response::new(chat).status_code(StatusCode::OK)),
+        Err(e @ Error::DirectChatNotModifiable(_)) => {
+            Err(Response::reject_with(e, StatusCode::CONFLICT))
+        }
+        Err(e @ Error::ChatActionNotAllowed(_, _, _)) => {
+            Err(Response::reject_with(e, StatusCode::FORBIDDEN))
+        }
+        Err(e @ Error::ParticipantNotFound(_, _)) => {
+            Err(Response::reject_with(e, StatusCode::NOT_FOUND))
+        }
+        Err(e) => Err(Response::message(e.to_string())
+            .status_code(StatusCode::BAD_REQUEST)
+            .reject()),
+    }
+}
@@ -0,0 +1,69 @@
+use futures::TryStreamExt;
+use warp::filters::multipart::{FormData, Part};
+use warp::http::StatusCode;
+use warp::reject::Rejection;
+
+use crate::application::service::Services;
+use crate::domain::auth::Claims;
+use crate::domain::chat::{Chat, ChatRole};
+use crate::error::Error;
+use crate::server::utils::File;
+use crate::server::utils::Response;
+
+pub async fn upload_chat_avatar(
+    claims: Claims,
+    chat: Chat,
+    services: Services,
+    form: FormData,
+) -> Result<impl warp::Reply, Rejection> {
+    let parts: Vec<Part> = form.try_collect().await.map_err(|e| {
+        Response::message(format!("Unable to read form, {}", e))
+            .status_code(StatusCode::BAD_REQUEST)
+            .reject()
+    })?;
+
+    if let Some(p) = parts.into_iter().find(|part| part.name() == "avatar") {
+        let part_bytes = File::from_part(p).await.map_err(|e| {
+            Response::message(e.message())
+                .status_code(StatusCode::BAD_REQUEST)
+                .reject()
+        })?;
+
+        chat.ensure_group()
+            .map_err(|e| Response::reject_with(e, StatusCode::CONFLICT))?;
+        chat.authorize(&claims.user_id, ChatRole::Admin, "update the chat")
+            .map_err(|e| Response::reject_with(e, StatusCode::FORBIDDEN))?;
+
+        let avatar = services
+            .avatar_service
+            .create(part_bytes.bytes().as_slice(), &claims.user_id)
+            .await
+            .map_err(|e| {
+                Response::message(e.message())
+                    .status_code(StatusCode::BAD_REQUEST)
+                    .reject()
+            })?;
+
+        let chat = services
+            .hub_service
+            .update_chat_avatar(&claims.user_id, &chat.id, &avatar.id)
+            .await
+            .map_err(|e| match e {
+                Error::ChatActionNotAllowed(_, _, _) => {
+                    Response::reject_with(e, StatusCode::FORBIDDEN)
+                }
+                Error::DirectChatNotModifiable(_) => Response::reject_with(e, StatusCode::CONFLICT),
+                _ => Response::message(e.message())
+                    .status_code(StatusCode::BAD_REQUEST)
+                    .reject(),
+            })?;
+
+        return Ok(Response::new(chat));
+    }
+
+    Err(
+        Response::message(String::from("The form field \"avatar\" is required"))
+            .status_code(StatusCode::BAD_REQUEST)
+            .reject(),
+    )
+}
@@ -1,5 +1,7 @@
 pub mod auth;
 pub mod chats;
 pub mod files;
+pub mod presence;
 pub mod profiles;
 pub mod rejection;
+pub mod search;
@@ -0,0 +1,58 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use warp::http::StatusCode;
+use warp::reject::Rejection;
+
+use crate::application::service::Services;
+use crate::domain::auth::Claims;
+use crate::domain::chat::Presence;
+use crate::server::utils::Response;
+
+/// Query parameters expected by the `/presence` endpoint.
+///
+/// `users_ids` is a comma separated list of user IDs
+#[derive(Deserialize)]
+pub struct FetchPresenceQueryParams {
+    users_ids: String,
+}
+
+#[derive(Serialize)]
+pub struct FetchPresenceResponse {
+    presence: Vec<Presence>,
+}
+
+pub async fn fetch_presence(
+    claims: Claims,
+    services: Services,
+    qparams: FetchPresenceQueryParams,
+) -> Result<impl warp::Reply, Rejection> {
+    let users_ids = match qparams
+        .users_ids
+        .split(',')
+        .filter(|id| !id.is_empty())
+        .map(Uuid::from_str)
+        .collect::<Result<Vec<Uuid>, _>>()
+    {
+        Ok(users_ids) => users_ids,
+        Err(e) => {
+            return Err(Response::message(e.to_string())
+                .status_code(StatusCode::BAD_REQUEST)
+                .reject())
+        }
+    };
+
+    match services
+        .hub_service
+        .fetch_presence(&claims.user_id, &users_ids)
+        .await
+    {
+        Ok(presence) => {
+            Ok(Response::new(FetchPresenceResponse { presence }).status_code(StatusCode::OK))
+        }
+        Err(e) => Err(Response::message(e.to_string())
+            .status_code(StatusCode::BAD_REQUEST)
+            .reject()),
+    }
+}
@@ -0,0 +1,3 @@
+mod fetch_presence;
+
+pub use fetch_presence::*;
@@ -0,0 +1,3 @@
+mod search_messages;
+
+pub use search_messages::*;
@@ -0,0 +1,59 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+use warp::http::StatusCode;
+use warp::reject::Rejection;
+
+use crate::application::service::Services;
+use crate::domain::auth::Claims;
+use crate::domain::chat::MessagesSearch;
+use crate::server::utils::Response;
+
+/// Amount of results retrieved when no `limit` is provided
+const DEFAULT_RESULTS_LIMIT: i64 = 20;
+
+/// Query parameters expected by the `/search/messages` endpoint.
+///
+/// `from` and `to` are RFC 3339 dates, `offset` is the `next` offset
+/// retrieved on a previous `SearchPage`
+#[derive(Deserialize)]
+pub struct SearchMessagesQueryParams {
+    q: String,
+    chat_id: Option<Uuid>,
+    author_id: Option<Uuid>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+pub async fn search_messages(
+    claims: Claims,
+    services: Services,
+    qparams: SearchMessagesQueryParams,
+) -> Result<impl warp::Reply, Rejection> {
+    let search = MessagesSearch {
+        query: qparams.q,
+        chat_id: qparams.chat_id,
+        author_id: qparams.author_id,
+        from: qparams.from,
+        to: qparams.to,
+    };
+
+    match services
+        .hub_service
+        .chat_provider
+        .search_messages(
+            &claims.user_id,
+            &search,
+            qparams.limit.unwrap_or(DEFAULT_RESULTS_LIMIT),
+            qparams.offset.unwrap_or(0),
+        )
+        .await
+    {
+        Ok(page) => Ok(Response::new(page).status_code(StatusCode::OK)),
+        Err(e) => Err(Response::message(e.to_string())
+            .status_code(StatusCode::BAD_REQUEST)
+            .reject()),
+    }
+}
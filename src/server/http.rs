@@ -5,12 +5,12 @@ use warp::http::{self, StatusCode};
 use warp::Filter;
 
 use crate::application::service::Services;
-use crate::domain::chat::{FrontEnd, Input, Proto};
+use crate::domain::chat::{FrontEnd, InputEnvelope};
 use crate::infrastructure::database::{get_db_pool, ping};
 use crate::server::utils::Response;
 
 use super::handler;
-use super::middleware::{with_authorization, with_service};
+use super::middleware::{with_authorization, with_chat_membership, with_service};
 
 const MAX_FILE_SIZE: u64 = 1_000_000;
 
@@ -35,7 +35,7 @@ impl Http {
         ping().await.expect("Unable to PING Database");
 
         let db_pool = get_db_pool().await;
-        let (chat_input_tx, chat_input_rx) = unbounded_channel::<Proto<Input>>();
+        let (chat_input_tx, chat_input_rx) = unbounded_channel::<InputEnvelope>();
         let services = Services::init(db_pool);
         let cors = warp::cors()
             .allow_any_origin()
@@ -49,6 +49,8 @@ impl Http {
                 http::Method::OPTIONS,
                 http::Method::POST,
                 http::Method::PUT,
+                http::Method::PATCH,
+                http::Method::DELETE,
             ]);
 
         let api = warp::path("api");
@@ -58,7 +60,9 @@ impl Http {
         let auth = api_v1.and(warp::path("auth"));
         let chats = api_v1.and(warp::path("chats"));
         let files = api_v1.and(warp::path("files"));
+        let presence = api_v1.and(warp::path("presence"));
         let profiles = api_v1.and(warp::path("profiles"));
+        let search = api_v1.and(warp::path("search"));
 
         let chat_web_socket = chats
             .and(warp::ws::ws())
@@ -69,7 +73,7 @@ impl Http {
                 move |ws: warp::ws::Ws,
                       services: Services,
                       qparams: ChatQueryParams,
-                      chat_input_tx: UnboundedSender<Proto<Input>>| {
+                      chat_input_tx: UnboundedSender<InputEnvelope>| {
                     ws.on_upgrade(move |web_socket| async move {
                         if let Some(frontend) = &qparams.frontend {
                             if let Ok(frontend) = FrontEnd::from_str(frontend.as_str()) {
@@ -130,6 +134,21 @@ impl Http {
             .and(warp::path::param())
             .and_then(handler::files::download);
 
+        let fetch_presence = presence
+            .and(warp::path::end())
+            .and(with_authorization())
+            .and(with_service(services.clone()))
+            .and(warp::query())
+            .and_then(handler::presence::fetch_presence);
+
+        let search_messages = search
+            .and(warp::path("messages"))
+            .and(warp::path::end())
+            .and(with_authorization())
+            .and(with_service(services.clone()))
+            .and(warp::query())
+            .and_then(handler::search::search_messages);
+
         let upload_avatar = profiles
             .and(warp::path("avatar"))
             .and(with_authorization())
@@ -138,39 +157,258 @@ impl Http {
             .and_then(handler::profiles::upload_avatar);
 
         let create_chat = chats
+            .and(warp::path::end())
             .and(with_authorization())
             .and(with_service(services.clone()))
             .and(warp::body::json())
             .and_then(handler::chats::create_chat);
 
+        let find_or_create_direct_chat = chats
+            .and(warp::path("direct"))
+            .and(warp::path::end())
+            .and(with_authorization())
+            .and(with_service(services.clone()))
+            .and(warp::body::json())
+            .and_then(handler::chats::find_or_create_direct_chat);
+
         let find_user_chats = chats
+            .and(warp::path::end())
             .and(with_authorization())
             .and(with_service(services.clone()))
+            .and(warp::query())
             .and_then(handler::chats::find_user_chats);
 
         let find_chat = chats
-            .and(with_authorization())
-            .and(with_service(services.clone()))
-            .and(warp::path::param())
+            .and(with_chat_membership(services.clone()))
+            .and(warp::path::end())
             .and_then(handler::chats::find_chat);
 
         let fetch_chat_messages = chats
-            .and(with_authorization())
+            .and(with_chat_membership(services.clone()))
             .and(with_service(services.clone()))
-            .and(warp::path::param())
             .and(warp::path("messages"))
+            .and(warp::path::end())
+            .and(warp::query())
             .and_then(handler::chats::fetch_chat_messages);
 
+        let fetch_thread = chats
+            .and(with_chat_membership(services.clone()))
+            .and(with_service(services.clone()))
+            .and(warp::path("messages"))
+            .and(warp::path::param())
+            .and(warp::path("thread"))
+            .and(warp::path::end())
+            .and(warp::query())
+            .and_then(handler::chats::fetch_thread);
+
+        let update_chat = chats
+            .and(with_chat_membership(services.clone()))
+            .and(with_service(services.clone()))
+            .and(warp::path::end())
+            .and(warp::body::json())
+            .and_then(handler::chats::update_chat);
+
+        let update_chat_settings = chats
+            .and(with_chat_membership(services.clone()))
+            .and(with_service(services.clone()))
+            .and(warp::path("settings"))
+            .and(warp::path::end())
+            .and(warp::body::json())
+            .and_then(handler::chats::update_chat_settings);
+
+        let update_message_ttl = chats
+            .and(with_chat_membership(services.clone()))
+            .and(with_service(services.clone()))
+            .and(warp::path("retention"))
+            .and(warp::path::end())
+            .and(warp::body::json())
+            .and_then(handler::chats::update_message_ttl);
+
+        let upload_chat_avatar = chats
+            .and(with_chat_membership(services.clone()))
+            .and(with_service(services.clone()))
+            .and(warp::path("avatar"))
+            .and(warp::path::end())
+            .and(warp::multipart::form().max_length(MAX_FILE_SIZE))
+            .and_then(handler::chats::upload_chat_avatar);
+
+        let add_participants = chats
+            .and(with_chat_membership(services.clone()))
+            .and(with_service(services.clone()))
+            .and(warp::path("participants"))
+            .and(warp::path::end())
+            .and(warp::body::json())
+            .and_then(handler::chats::add_participants);
+
+        let remove_participant = chats
+            .and(with_chat_membership(services.clone()))
+            .and(with_service(services.clone()))
+            .and(warp::path("participants"))
+            .and(warp::path::param())
+            .and(warp::path::end())
+            .and_then(handler::chats::remove_participant);
+
+        let update_participant_role = chats
+            .and(with_chat_membership(services.clone()))
+            .and(with_service(services.clone()))
+            .and(warp::path("participants"))
+            .and(warp::path::param())
+            .and(warp::path("role"))
+            .and(warp::path::end())
+            .and(warp::body::json())
+            .and_then(handler::chats::update_participant_role);
+
+        let mark_read = chats
+            .and(with_chat_membership(services.clone()))
+            .and(with_service(services.clone()))
+            .and(warp::path("read"))
+            .and(warp::path::end())
+            .and(warp::body::json())
+            .and_then(handler::chats::mark_read);
+
+        let leave_chat = chats
+            .and(with_chat_membership(services.clone()))
+            .and(with_service(services.clone()))
+            .and(warp::path("leave"))
+            .and(warp::path::end())
+            .and_then(handler::chats::leave_chat);
+
+        let edit_message = chats
+            .and(with_chat_membership(services.clone()))
+            .and(with_service(services.clone()))
+            .and(warp::path("messages"))
+            .and(warp::path::param())
+            .and(warp::path::end())
+            .and(warp::body::json())
+            .and_then(handler::chats::edit_message);
+
+        let delete_message = chats
+            .and(with_chat_membership(services.clone()))
+            .and(with_service(services.clone()))
+            .and(warp::path("messages"))
+            .and(warp::path::param())
+            .and(warp::path::end())
+            .and_then(handler::chats::delete_message);
+
+        let fetch_pins = chats
+            .and(with_chat_membership(services.clone()))
+            .and(with_service(services.clone()))
+            .and(warp::path("pins"))
+            .and(warp::path::end())
+            .and_then(handler::chats::fetch_pins);
+
+        let pin_message = chats
+            .and(with_chat_membership(services.clone()))
+            .and(with_service(services.clone()))
+            .and(warp::path("messages"))
+            .and(warp::path::param())
+            .and(warp::path("pin"))
+            .and(warp::path::end())
+            .and_then(handler::chats::pin_message);
+
+        let unpin_message = chats
+            .and(with_chat_membership(services.clone()))
+            .and(with_service(services.clone()))
+            .and(warp::path("messages"))
+            .and(warp::path::param())
+            .and(warp::path("pin"))
+            .and(warp::path::end())
+            .and_then(handler::chats::unpin_message);
+
+        let forward_message = chats
+            .and(with_chat_membership(services.clone()))
+            .and(with_service(services.clone()))
+            .and(warp::path("messages"))
+            .and(warp::path::param())
+            .and(warp::path("forward"))
+            .and(warp::path::end())
+            .and(warp::body::json())
+            .and_then(handler::chats::forward_message);
+
+        let fetch_scheduled_messages = chats
+            .and(with_chat_membership(services.clone()))
+            .and(with_service(services.clone()))
+            .and(warp::path("scheduled-messages"))
+            .and(warp::path::end())
+            .and_then(handler::chats::fetch_scheduled_messages);
+
+        let schedule_message = chats
+            .and(with_chat_membership(services.clone()))
+            .and(with_service(services.clone()))
+            .and(warp::path("scheduled-messages"))
+            .and(warp::path::end())
+            .and(warp::body::json())
+            .and_then(handler::chats::schedule_message);
+
+        let cancel_scheduled_message = chats
+            .and(with_chat_membership(services.clone()))
+            .and(with_service(services.clone()))
+            .and(warp::path("scheduled-messages"))
+            .and(warp::path::param())
+            .and(warp::path::end())
+            .and_then(handler::chats::cancel_scheduled_message);
+
+        let add_reaction = chats
+            .and(with_chat_membership(services.clone()))
+            .and(with_service(services.clone()))
+            .and(warp::path("messages"))
+            .and(warp::path::param())
+            .and(warp::path("reactions"))
+            .and(warp::path::end())
+            .and(warp::body::json())
+            .and_then(handler::chats::add_reaction);
+
+        let remove_reaction = chats
+            .and(with_chat_membership(services.clone()))
+            .and(with_service(services.clone()))
+            .and(warp::path("messages"))
+            .and(warp::path::param())
+            .and(warp::path("reactions"))
+            .and(warp::path::end())
+            .and(warp::body::json())
+            .and_then(handler::chats::remove_reaction);
+
         let get_routes = warp::get().and(
             login
                 .or(me)
                 .or(download_file)
+                .or(fetch_presence)
+                .or(search_messages)
                 .or(fetch_chat_messages)
+                .or(fetch_thread)
+                .or(fetch_pins)
+                .or(fetch_scheduled_messages)
                 .or(find_chat.or(find_user_chats)),
         );
-        let post_routes =
-            warp::post().and(signup.or(upload_file).or(upload_avatar).or(create_chat));
-        let routes = chat_web_socket.or(get_routes.or(post_routes));
+        let post_routes = warp::post().and(
+            signup
+                .or(upload_file)
+                .or(upload_avatar)
+                .or(create_chat)
+                .or(find_or_create_direct_chat)
+                .or(upload_chat_avatar)
+                .or(add_participants)
+                .or(mark_read)
+                .or(leave_chat)
+                .or(add_reaction)
+                .or(pin_message)
+                .or(schedule_message)
+                .or(forward_message),
+        );
+        let put_routes = warp::put().and(update_participant_role.or(update_message_ttl));
+        let patch_routes = warp::patch().and(update_chat.or(update_chat_settings).or(edit_message));
+        let delete_routes = warp::delete().and(
+            remove_participant
+                .or(delete_message)
+                .or(remove_reaction)
+                .or(unpin_message)
+                .or(cancel_scheduled_message),
+        );
+        let routes = chat_web_socket.or(get_routes
+            .or(post_routes)
+            .or(put_routes)
+            .or(patch_routes)
+            .or(delete_routes));
         let routes = routes.recover(handler::rejection::handle_rejection);
 
         let serve_process = warp::serve(routes.with(cors)).bind(([127, 0, 0, 1], self.port));
@@ -1,5 +1,7 @@
 mod with_authorization;
+mod with_chat_membership;
 mod with_service;
 
 pub use with_authorization::*;
+pub use with_chat_membership::*;
 pub use with_service::*;
@@ -0,0 +1,48 @@
+use uuid::Uuid;
+use warp::http::StatusCode;
+use warp::{Filter, Rejection};
+
+use crate::application::service::Services;
+use crate::domain::auth::Claims;
+use crate::domain::chat::Chat;
+use crate::error::Error;
+use crate::server::utils::Response;
+
+use super::{with_authorization, with_service};
+
+/// Authorizes the request and extracts the `Chat` with the ID provided as
+/// path parameter, making sure the authenticated user is one of its
+/// participants.
+///
+/// Rejects with `404 Not Found` if the chat doesn't exists and with
+/// `403 Forbidden` if the user doesn't belong to the chat.
+pub fn with_chat_membership(
+    services: Services,
+) -> impl Filter<Extract = (Claims, Chat), Error = Rejection> + Clone {
+    with_authorization()
+        .and(with_service(services))
+        .and(warp::path::param())
+        .and_then(
+            |claims: Claims, services: Services, chat_id: Uuid| async move {
+                let chat = services
+                    .hub_service
+                    .chat_provider
+                    .find_chat(&chat_id)
+                    .await
+                    .map_err(|e| match e {
+                        Error::ChatNotFound => Response::reject_with(e, StatusCode::NOT_FOUND),
+                        _ => Response::reject_with(e, StatusCode::INTERNAL_SERVER_ERROR),
+                    })?;
+
+                if !chat.participants_ids.contains(&claims.user_id) {
+                    return Err(Response::reject_with(
+                        Error::UserDoesntBelongToChat(claims.user_id, chat_id),
+                        StatusCode::FORBIDDEN,
+                    ));
+                }
+
+                Ok((claims, chat))
+            },
+        )
+        .untuple_one()
+}